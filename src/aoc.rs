@@ -1,51 +1,817 @@
-use time::PreciseTime;
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::env;
 use std::fs::File;
-use std::io::Read;
-use std::fmt::Display;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use crate::bench::{bench, BenchOptions, Stats};
+use crate::parse::ParseError;
 
-pub fn load_input(name: &str) -> String {
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+/// The root of the project, which holds the inputs, answers and benchmark baseline whatever the
+/// current directory is.
+pub fn project_dir() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+pub fn input_dir() -> PathBuf {
+    match env::var_os(INPUT_DIR_VAR) {
+        Some(dir) => PathBuf::from(dir),
+        None => project_dir().join("input"),
+    }
+}
+
+pub fn input_path(name: &str) -> PathBuf {
+    input_dir().join(format!("{}.txt", name))
+}
+
+/// Examples sit next to the real input, e.g. `day10.example1` for `input/day10.example1.txt`.
+pub fn example_name(name: &str, example: &str) -> String {
+    format!("{}.{}", name, example)
+}
+
+pub fn answer_path(name: &str, part: u32) -> PathBuf {
+    project_dir().join("answers").join(format!("{}.p{}.txt", name, part))
+}
+
+pub fn baseline_path() -> PathBuf {
+    project_dir().join("bench-baseline.tsv")
+}
+
+#[derive(Debug)]
+pub struct InputError {
+    pub source: String,
+    pub error: io::Error,
+}
+
+impl InputError {
+    pub fn is_not_found(&self) -> bool {
+        self.error.kind() == io::ErrorKind::NotFound
+    }
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Could not read {}: {}", self.source, self.error)?;
+
+        if self.is_not_found() && env::var_os(INPUT_DIR_VAR).is_none() {
+            write!(f, " (set {} to read inputs from another directory)", INPUT_DIR_VAR)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads the input from `path` if given (`-` being stdin), and otherwise `name` from the input
+/// directory.
+pub fn read_input(name: &str, path: Option<&str>) -> Result<String, InputError> {
+    match path {
+        Some("-") => {
+            let mut buf = String::with_capacity(2048);
+            match io::stdin().read_to_string(&mut buf) {
+                Ok(_) => Ok(buf),
+                Err(error) => Err(InputError { source: "stdin".to_owned(), error }),
+            }
+        }
+        Some(path) => read_input_file(Path::new(path)),
+        None => load_input(name),
+    }
+}
+
+pub fn load_input(name: &str) -> Result<String, InputError> {
+    read_input_file(&input_path(name))
+}
+
+pub fn load_example(name: &str, example: &str) -> Result<String, InputError> {
+    load_input(&example_name(name, example))
+}
+
+fn read_input_file(path: &Path) -> Result<String, InputError> {
     let mut buf = String::with_capacity(2048);
 
-    match File::open(format!("./input/{}.txt", name)) {
-        Ok(mut file) => {
-            file.read_to_string(&mut buf).unwrap();
+    match File::open(path).and_then(|mut file| file.read_to_string(&mut buf)) {
+        Ok(_) => Ok(buf),
+        Err(error) => Err(InputError { source: path.display().to_string(), error }),
+    }
+}
+
+pub type Method<I, T> = (&'static str, fn(&I) -> T);
+
+pub trait Solution: 'static {
+    type Input: Send + Sync + 'static;
+    type Part1: Display + 'static;
+    type Part2: Display + 'static;
+
+    const DAY: u32;
+    const PARSE_ITERATIONS: usize = 1000;
+    const PART1_ITERATIONS: usize = 1000;
+    const PART2_ITERATIONS: usize = 1000;
+    const PART1_METHOD: &'static str = "";
+    const PART2_METHOD: &'static str = "";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    fn part1(input: &Self::Input) -> Self::Part1;
+    fn part2(input: &Self::Input) -> Self::Part2;
+
+    fn part1_alternatives() -> Vec<Method<Self::Input, Self::Part1>> {
+        Vec::new()
+    }
+
+    fn part2_alternatives() -> Vec<Method<Self::Input, Self::Part2>> {
+        Vec::new()
+    }
+}
+
+pub struct Day {
+    pub day: u32,
+    pub run: fn(&str, &RunOptions) -> Report,
+}
+
+impl Day {
+    pub fn name(&self) -> String {
+        day_name(self.day)
+    }
+
+    pub fn new<S: Solution>() -> Day {
+        Day {
+            day: S::DAY,
+            run: run_solution::<S>,
         }
-        Err(e) => {
-            panic!("Could not load file ./input/{}.txt: {}", name, e);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Failure {
+    Invalid(ParseError),
+    Timeout(Duration),
+    Panic(String),
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Failure::Invalid(error) => write!(f, "INVALID INPUT at {}", error),
+            Failure::Timeout(timeout) => write!(f, "TIMEOUT after {}", format_time(timeout.as_nanos() as i64)),
+            Failure::Panic(message) => write!(f, "PANIC: {}", message),
         }
     }
+}
 
-    buf
+/// A failed part has the failure as its answer and no timings.
+pub struct PartReport {
+    pub part: u32,
+    pub method: String,
+    pub label: String,
+    pub answer: String,
+    pub stats: Stats,
+    pub failure: Option<Failure>,
 }
 
-pub fn run_once<T>(callback: impl Fn() -> T) -> (T, i64) {
-    let start = PreciseTime::now();
-    let result = callback();
-    let end = PreciseTime::now();
+/// If parsing fails, `parse_failure` is set and there are no parts.
+pub struct Report {
+    pub day: u32,
+    pub parse: Stats,
+    pub parse_failure: Option<Failure>,
+    pub parts: Vec<PartReport>,
+    pub elapsed: i64,
+}
 
-    (result, start.to(end).num_nanoseconds().unwrap())
+impl Report {
+    pub fn name(&self) -> String {
+        day_name(self.day)
+    }
+
+    /// The answer of every part, or the failure of the parse step.
+    pub fn results(&self) -> Vec<(&str, String)> {
+        match &self.parse_failure {
+            Some(failure) => vec![("Parse", failure.to_string())],
+            None => self.parts.iter().map(|p| (p.label.as_str(), p.answer.clone())).collect(),
+        }
+    }
+
+    /// The stats of the parse step and every part that did not fail.
+    pub fn timings(&self) -> Vec<(&str, &Stats)> {
+        if self.parse_failure.is_some() {
+            return Vec::new();
+        }
+
+        Some(("Parse", &self.parse)).into_iter()
+            .chain(self.parts.iter().filter(|p| p.failure.is_none()).map(|p| (p.label.as_str(), &p.stats)))
+            .collect()
+    }
+
+    pub fn failures(&self) -> usize {
+        self.parse_failure.iter().count() + self.parts.iter().filter(|p| p.failure.is_some()).count()
+    }
 }
 
-pub fn run_many<T>(times: usize, callback: impl Fn() -> T) -> (T, i64) {
-    let start = PreciseTime::now();
-    let mut result = callback();
-    for _ in 1..times {
-        result =    callback();
+pub fn day_name(day: u32) -> String {
+    format!("day{:02}", day)
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct RunOptions {
+    pub part: Option<u32>,
+    pub bench: bool,
+    pub warmup: Duration,
+    pub min_duration: Duration,
+    pub timeout: Option<Duration>,
+}
+
+impl RunOptions {
+    fn bench_options(&self, times: usize) -> BenchOptions {
+        if self.bench {
+            BenchOptions {
+                iterations: times,
+                warmup: self.warmup,
+                min_duration: self.min_duration,
+            }
+        } else {
+            BenchOptions::iterations(1)
+        }
     }
-    let end = PreciseTime::now();
 
-    (result, start.to(end).num_nanoseconds().unwrap() / times as i64)
+    fn includes(&self, part: u32) -> bool {
+        self.part.is_none() || self.part == Some(part)
+    }
 }
 
-pub fn run_many_mut<T>(times: usize, mut callback: impl FnMut() -> T) -> (T, i64) {
-    let start = PreciseTime::now();
-    let mut result = callback();
-    for _ in 1..times {
-        result =    callback();
+pub fn run_solution<S: Solution>(input: &str, options: &RunOptions) -> Report {
+    let start = Instant::now();
+    let mut report = Report {
+        day: S::DAY,
+        parse: Stats::default(),
+        parse_failure: None,
+        parts: Vec::with_capacity(2),
+        elapsed: 0,
+    };
+
+    let parse_options = options.bench_options(S::PARSE_ITERATIONS);
+    let input = input.to_owned();
+    let parsed = match isolate(options.timeout, move |ran_once| bench(&parse_options, || {
+        let parsed = S::parse(&input);
+        ran_once();
+        parsed
+    })) {
+        Ok((Ok(parsed), stats)) => {
+            report.parse = stats;
+            Arc::new(parsed)
+        }
+        Ok((Err(error), _)) => {
+            report.parse_failure = Some(Failure::Invalid(error));
+            report.elapsed = start.elapsed().as_nanos() as i64;
+            return report;
+        }
+        Err(failure) => {
+            report.parse_failure = Some(failure);
+            report.elapsed = start.elapsed().as_nanos() as i64;
+            return report;
+        }
+    };
+
+    if options.includes(1) {
+        let bench_options = options.bench_options(S::PART1_ITERATIONS);
+
+        run_part(&mut report.parts, 1, S::PART1_METHOD, options.timeout, bench_options, &parsed, S::part1);
+        for (method, callback) in S::part1_alternatives() {
+            run_part(&mut report.parts, 1, method, options.timeout, bench_options, &parsed, callback);
+        }
+    }
+
+    if options.includes(2) {
+        let bench_options = options.bench_options(S::PART2_ITERATIONS);
+
+        run_part(&mut report.parts, 2, S::PART2_METHOD, options.timeout, bench_options, &parsed, S::part2);
+        for (method, callback) in S::part2_alternatives() {
+            run_part(&mut report.parts, 2, method, options.timeout, bench_options, &parsed, callback);
+        }
+    }
+
+    report.elapsed = start.elapsed().as_nanos() as i64;
+
+    report
+}
+
+fn run_part<I, T>(parts: &mut Vec<PartReport>, part: u32, method: &str, timeout: Option<Duration>, options: BenchOptions, input: &Arc<I>, callback: fn(&I) -> T)
+    where I: Send + Sync + 'static, T: Display + 'static
+{
+    let input = Arc::clone(input);
+    let result = isolate(timeout, move |ran_once| {
+        let (result, stats) = bench(&options, || {
+            let result = callback(&input);
+            ran_once();
+            result
+        });
+
+        (result.to_string(), stats)
+    });
+
+    let label = if method.is_empty() {
+        format!("P{}", part)
+    } else {
+        format!("P{}: {}", part, method)
+    };
+
+    parts.push(match result {
+        Ok((answer, stats)) => PartReport { part, method: method.to_owned(), label, answer, stats, failure: None },
+        Err(failure) => PartReport {
+            part, label,
+            method: method.to_owned(),
+            answer: failure.to_string(),
+            stats: Stats::default(),
+            failure: Some(failure),
+        },
+    });
+}
+
+/// Runs the job and catches any panic. The job is given a function to call each time it has
+/// run the code being measured, and the timeout only covers the first run, so benchmarks can
+/// take as many runs as they need. With a timeout, the job runs on its own thread, which is left
+/// behind if it doesn't finish in time since there is no way to stop it.
+fn isolate<T: Send + 'static>(timeout: Option<Duration>, job: impl FnOnce(&dyn Fn()) -> T + Send + 'static) -> Result<T, Failure> {
+    let timeout = match timeout {
+        Some(timeout) => timeout,
+        None => return panic::catch_unwind(AssertUnwindSafe(|| job(&|| {}))).map_err(panic_failure),
+    };
+
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let signalled = Cell::new(false);
+        let ran_once = || {
+            if !signalled.replace(true) {
+                let _ = sender.send(None);
+            }
+        };
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| job(&ran_once)));
+        let _ = sender.send(Some(result));
+    });
+
+    let result = match receiver.recv_timeout(timeout) {
+        Ok(Some(result)) => Ok(result),
+        Ok(None) => receiver.recv().map(|result| result.expect("the job only signals once")),
+        Err(mpsc::RecvTimeoutError::Timeout) => return Err(Failure::Timeout(timeout)),
+        Err(mpsc::RecvTimeoutError::Disconnected) => Err(mpsc::RecvError),
+    };
+
+    match result {
+        Ok(result) => result.map_err(panic_failure),
+        Err(_) => Err(Failure::Panic("the thread went away".to_owned())),
+    }
+}
+
+fn panic_failure(payload: Box<dyn std::any::Any + Send>) -> Failure {
+    let message = if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_owned()
+    };
+
+    Failure::Panic(message)
+}
+
+pub fn load_answer(name: &str, part: u32) -> Option<String> {
+    std::fs::read_to_string(answer_path(name, part)).ok()
+        .map(|answer| normalize_answer(&answer))
+        .filter(|answer| !answer.is_empty())
+}
+
+fn normalize_answer(answer: &str) -> String {
+    let lines: Vec<&str> = answer.lines().map(|l| l.trim_end()).collect();
+
+    lines.join("\n").trim().to_owned()
+}
+
+/// A part whose answer did not match the stored one, or that had no stored answer to check.
+pub struct Mismatch {
+    pub day: u32,
+    pub label: String,
+    pub expected: Option<String>,
+    pub actual: String,
+}
+
+impl Mismatch {
+    pub fn is_unrecorded(&self) -> bool {
+        self.expected.is_none()
+    }
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let expected = match self.expected {
+            Some(ref expected) => expected,
+            None => return write!(f, "No answer recorded (Day {}, {})", self.day, self.label),
+        };
+
+        if expected.contains('\n') || self.actual.contains('\n') {
+            write!(f, "Wrong answer (Day {}, {}): expected\n{}\ngot\n{}", self.day, self.label, expected, self.actual)
+        } else {
+            write!(f, "Wrong answer (Day {}, {}): expected {}, got {}", self.day, self.label, expected, self.actual)
+        }
+    }
+}
+
+/// Compares every part in the report, alternatives included, with the stored answers. Parts
+/// without an answer file are returned as unrecorded, since they couldn't be checked.
+pub fn verify_report(report: &Report) -> Vec<Mismatch> {
+    verify_with(report, load_answer)
+}
+
+fn verify_with(report: &Report, load: impl Fn(&str, u32) -> Option<String>) -> Vec<Mismatch> {
+    let name = report.name();
+    let mut mismatches = Vec::new();
+
+    for part in report.parts.iter() {
+        let expected = load(&name, part.part);
+        let actual = normalize_answer(&part.answer);
+
+        if expected.as_ref() != Some(&actual) {
+            mismatches.push(Mismatch {
+                day: report.day,
+                label: part.label.clone(),
+                expected, actual,
+            });
+        }
+    }
+
+    mismatches
+}
+
+/// Median timings keyed by day and label, stored as one tab-separated `day label ns` line each.
+#[derive(Default)]
+pub struct Baseline {
+    times: BTreeMap<(u32, String), i64>,
+}
+
+impl Baseline {
+    pub fn get(&self, day: u32, label: &str) -> Option<i64> {
+        self.times.get(&(day, label.to_owned())).cloned()
     }
-    let end = PreciseTime::now();
 
-    (result, start.to(end).num_nanoseconds().unwrap() / times as i64)
+    pub fn record(&mut self, report: &Report) {
+        for (label, stats) in report.timings() {
+            self.times.insert((report.day, label.to_owned()), stats.median);
+        }
+    }
+
+    pub fn compare(&self, report: &Report, threshold: f64) -> Vec<Comparison> {
+        let mut comparisons = Vec::with_capacity(report.parts.len() + 1);
+
+        for (label, stats) in report.timings() {
+            let ns = stats.median;
+            if let Some(baseline_ns) = self.get(report.day, label) {
+                let change = if baseline_ns > 0 {
+                    (ns - baseline_ns) as f64 * 100.0 / baseline_ns as f64
+                } else {
+                    0.0
+                };
+
+                comparisons.push(Comparison {
+                    day: report.day,
+                    label: label.to_owned(),
+                    regression: change > threshold,
+                    baseline_ns, ns, change,
+                });
+            }
+        }
+
+        comparisons
+    }
+
+    pub fn parse(data: &str) -> Baseline {
+        let mut baseline = Baseline::default();
+
+        for line in data.lines() {
+            let mut tokens = line.split('\t');
+            let day = tokens.next().and_then(|t| t.parse().ok());
+            let label = tokens.next();
+            let ns = tokens.next().and_then(|t| t.parse().ok());
+
+            if let (Some(day), Some(label), Some(ns)) = (day, label, ns) {
+                baseline.times.insert((day, label.to_owned()), ns);
+            }
+        }
+
+        baseline
+    }
+
+    pub fn load(path: &Path) -> io::Result<Baseline> {
+        match std::fs::read_to_string(path) {
+            Ok(data) => Ok(Baseline::parse(&data)),
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(Baseline::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        std::fs::write(path, self.to_string())
+    }
+}
+
+impl Display for Baseline {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for ((day, label), ns) in self.times.iter() {
+            writeln!(f, "{}\t{}\t{}", day, label, ns)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct Comparison {
+    pub day: u32,
+    pub label: String,
+    pub baseline_ns: i64,
+    pub ns: i64,
+    pub change: f64,
+    pub regression: bool,
+}
+
+pub fn print_comparisons(comparisons: &[Comparison]) {
+    for c in comparisons.iter() {
+        println!(
+            "Change ({}): {} -> {} ({:+.1}%){}",
+            c.label, format_time(c.baseline_ns), format_time(c.ns), c.change,
+            if c.regression { " REGRESSION" } else { "" },
+        );
+    }
+}
+
+pub fn print_report(report: &Report) {
+    for (label, answer) in report.results() {
+        if answer.contains('\n') {
+            print_result_multiline(label, &answer);
+        } else {
+            print_result(label, &answer);
+        }
+    }
+
+    for (label, stats) in report.timings() {
+        print_stats(label, stats);
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+    Csv,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<OutputFormat, String> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            _ => Err(format!("Unknown output format: {}", s)),
+        }
+    }
+}
+
+pub const CSV_HEADER: &str = "day,kind,label,answer,ns,mean_ns,min_ns,p95_ns,stddev_ns,runs,outliers";
+
+/// One line per labelled result and timing, with all times in raw nanoseconds. The text
+/// format has no records; use `print_report` for that.
+pub fn format_records(report: &Report, format: OutputFormat) -> Vec<String> {
+    let mut records = Vec::with_capacity(report.parts.len() * 2 + 1);
+
+    for (label, answer) in report.results() {
+        records.push(match format {
+            OutputFormat::Text => continue,
+            OutputFormat::Json => format!(
+                "{{\"day\":{},\"kind\":\"result\",\"label\":{},\"answer\":{}}}",
+                report.day, json_string(label), json_string(&answer),
+            ),
+            OutputFormat::Csv => format!(
+                "{},result,{},{},,,,,,,", report.day, csv_field(label), csv_field(&answer),
+            ),
+        });
+    }
+
+    for (label, stats) in report.timings() {
+        records.push(match format {
+            OutputFormat::Text => continue,
+            OutputFormat::Json => format!(
+                "{{\"day\":{},\"kind\":\"time\",\"label\":{},\"ns\":{},\"mean_ns\":{},\"min_ns\":{},\"p95_ns\":{},\"stddev_ns\":{},\"runs\":{},\"outliers\":{}}}",
+                report.day, json_string(label), stats.median, stats.mean, stats.min, stats.p95,
+                stats.stddev, stats.runs, stats.outliers,
+            ),
+            OutputFormat::Csv => format!(
+                "{},time,{},,{},{},{},{},{},{},{}",
+                report.day, csv_field(label), stats.median, stats.mean, stats.min, stats.p95,
+                stats.stddev, stats.runs, stats.outliers,
+            ),
+        });
+    }
+
+    records
+}
+
+pub fn print_report_as(report: &Report, format: OutputFormat) {
+    if format == OutputFormat::Text {
+        print_report(report);
+        return;
+    }
+
+    for record in format_records(report, format) {
+        println!("{}", record);
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+
+    out
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_owned()
+    }
+}
+
+pub fn print_summary(reports: &[Report]) {
+    let mut rows: Vec<[String; 4]> = Vec::with_capacity(reports.len() * 4);
+    let mut total_ns = 0;
+
+    for report in reports.iter() {
+        let medians: BTreeMap<&str, i64> = report.timings().into_iter().map(|(label, stats)| (label, stats.median)).collect();
+        let answers: BTreeMap<&str, String> = report.results().into_iter().collect();
+        let labels = Some("Parse").into_iter().chain(report.parts.iter().map(|p| p.label.as_str()));
+
+        for label in labels {
+            let answer = match answers.get(label) {
+                Some(answer) if answer.contains('\n') => "(multiline)".to_owned(),
+                Some(answer) => answer.clone(),
+                None => String::new(),
+            };
+            let median = medians.get(label).cloned();
+
+            rows.push([report.day.to_string(), label.to_owned(), answer, median.map(format_time).unwrap_or_default()]);
+            total_ns += median.unwrap_or(0);
+        }
+    }
+    rows.push([String::new(), "Total".to_owned(), String::new(), format_time(total_ns)]);
+
+    let header = ["Day", "Label", "Answer", "Median"];
+    let mut widths = [0usize; 4];
+    for row in rows.iter().map(|r| [r[0].as_str(), r[1].as_str(), r[2].as_str(), r[3].as_str()]).chain(Some(header)) {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(cell.chars().count());
+        }
+    }
+
+    let print_row = |row: [&str; 4]| {
+        println!("{:>w0$} | {:w1$} | {:w2$} | {:>w3$}",
+                 row[0], row[1], row[2], row[3],
+                 w0 = widths[0], w1 = widths[1], w2 = widths[2], w3 = widths[3]);
+    };
+
+    print_row(header);
+    println!("{}-+-{}-+-{}-+-{}", "-".repeat(widths[0]), "-".repeat(widths[1]), "-".repeat(widths[2]), "-".repeat(widths[3]));
+    for row in rows.iter() {
+        print_row([&row[0], &row[1], &row[2], &row[3]]);
+    }
+}
+
+/// Shows how the days shared the time spent running them, which is more than the wall time when
+/// they ran on several threads.
+pub fn print_time_shares(reports: &[Report], wall_ns: i64, threads: usize) {
+    let total_ns: i64 = reports.iter().map(|r| r.elapsed).sum();
+
+    println!();
+    println!("Wall time: {} on {} thread(s), {} across all days", format_time(wall_ns), threads, format_time(total_ns));
+    for report in reports.iter() {
+        println!(
+            "Day {:>2}: {:>9} ({:>5.1}%)",
+            report.day, format_time(report.elapsed),
+            report.elapsed as f64 * 100.0 / total_ns.max(1) as f64,
+        );
+    }
+}
+
+/// A short hash of the answer, so the report can show whether answers changed without
+/// giving them away. This is 32-bit FNV-1a, which is stable between builds.
+pub fn answer_hash(answer: &str) -> String {
+    let hash = normalize_answer(answer).bytes()
+        .fold(0x811c_9dc5u32, |hash, b| (hash ^ b as u32).wrapping_mul(0x0100_0193));
+
+    format!("{:08x}", hash)
+}
+
+/// A Markdown table with a row per part, followed by a table for each part that has
+/// alternative methods to compare them side by side.
+pub fn markdown_report(reports: &[Report]) -> String {
+    let mut out = String::with_capacity(4096);
+
+    out.push_str("| Day | Part | Method | Answer hash | Median |\n");
+    out.push_str("|----:|-----:|--------|-------------|-------:|\n");
+    for report in reports.iter() {
+        if let Some(failure) = &report.parse_failure {
+            out.push_str(&format!("| {} | Parse | | {} | |\n", report.day, markdown_cell(&failure.to_string())));
+            continue;
+        }
+
+        out.push_str(&format!("| {} | Parse | | | {} |\n", report.day, format_time(report.parse.median)));
+        for part in report.parts.iter() {
+            out.push_str(&format!("| {} | {} | {} | {} | {} |\n", report.day, part.part, markdown_cell(&part.method), markdown_result(part), markdown_median(part)));
+        }
+    }
+
+    for report in reports.iter() {
+        for part in 1..=2 {
+            let methods: Vec<&PartReport> = report.parts.iter().filter(|p| p.part == part).collect();
+            if methods.len() < 2 {
+                continue;
+            }
+
+            let fastest = methods.iter()
+                .filter(|p| p.failure.is_none())
+                .map(|p| p.stats.median)
+                .min().unwrap_or(0);
+
+            out.push_str(&format!("\n### Day {}, part {}\n\n", report.day, part));
+            out.push_str("| Method | Answer hash | Median | Relative |\n");
+            out.push_str("|--------|-------------|-------:|---------:|\n");
+            for method in methods {
+                let relative = if method.failure.is_none() && fastest > 0 {
+                    format!("{:.2}x", method.stats.median as f64 / fastest as f64)
+                } else {
+                    String::new()
+                };
+
+                out.push_str(&format!("| {} | {} | {} | {} |\n", markdown_cell(&method.method), markdown_result(method), markdown_median(method), relative));
+            }
+        }
+    }
+
+    out
+}
+
+/// Escapes the pipes and line breaks that would otherwise end a table cell or row early.
+fn markdown_cell(text: &str) -> String {
+    text.trim_end().replace('|', "\\|").replace("\r\n", "<br>").replace('\n', "<br>")
+}
+
+fn markdown_result(part: &PartReport) -> String {
+    match &part.failure {
+        Some(Failure::Invalid(_)) => "INVALID INPUT".to_owned(),
+        Some(Failure::Timeout(_)) => "TIMEOUT".to_owned(),
+        Some(Failure::Panic(_)) => "PANIC".to_owned(),
+        None => format!("`{}`", answer_hash(&part.answer)),
+    }
+}
+
+fn markdown_median(part: &PartReport) -> String {
+    match part.failure {
+        Some(_) => String::new(),
+        None => format_time(part.stats.median),
+    }
+}
+
+pub fn run_once<T>(callback: impl Fn() -> T) -> (T, i64) {
+    run_many(1, callback)
+}
+
+pub fn run_many<T>(times: usize, callback: impl Fn() -> T) -> (T, i64) {
+    let (result, stats) = bench(&BenchOptions::iterations(times), callback);
+
+    (result, stats.mean)
+}
+
+pub fn run_many_mut<T>(times: usize, callback: impl FnMut() -> T) -> (T, i64) {
+    let (result, stats) = bench(&BenchOptions::iterations(times), callback);
+
+    (result, stats.mean)
 }
 
 pub fn print_result(label: &str, result: impl Display) {
@@ -57,13 +823,233 @@ pub fn print_result_multiline(label: &str, result: impl Display) {
 }
 
 pub fn print_time(label: &str, ns: i64) {
+    println!("Duration ({}): {}", label, format_time(ns));
+}
+
+pub fn print_stats(label: &str, stats: &Stats) {
+    if stats.runs > 1 {
+        println!(
+            "Duration ({}): {} (mean {}, min {}, p95 {}, stddev {}, {} runs, {} outliers)",
+            label, format_time(stats.median), format_time(stats.mean), format_time(stats.min),
+            format_time(stats.p95), format_time(stats.stddev), stats.runs, stats.outliers,
+        );
+    } else {
+        print_time(label, stats.median);
+    }
+}
+
+pub fn format_time(ns: i64) -> String {
     if ns > 1_000_000_000 {
-        println!("Duration ({}): {:.3}s", label, (ns as f64) / (1_000_000_000 as f64));
+        format!("{:.3}s", (ns as f64) / 1_000_000_000_f64)
     } else if ns > 1_000_000 {
-        println!("Duration ({}): {:.2}ms", label, (ns as f64) / (1_000_000 as f64));
+        format!("{:.2}ms", (ns as f64) / 1_000_000_f64)
     } else if ns > 1_000 {
-        println!("Duration ({}): {:.1}µs", label, (ns as f64) / (1_000 as f64));
+        format!("{:.1}µs", (ns as f64) / 1_000_f64)
     } else {
-        println!("Duration ({}): {}ns", label, ns);
+        format!("{}ns", ns)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::Scanner;
+
+    fn report(parse_ns: i64, part_ns: &[(&str, i64)]) -> Report {
+        let stats = |ns| Stats { median: ns, ..Stats::default() };
+
+        Report {
+            day: 4,
+            parse: stats(parse_ns),
+            parts: part_ns.iter().map(|(label, ns)| PartReport {
+                part: 1,
+                method: label.trim_start_matches("P1").trim_start_matches(": ").to_owned(),
+                label: label.to_string(),
+                answer: String::new(),
+                stats: stats(*ns),
+                failure: None,
+            }).collect(),
+            parse_failure: None,
+            elapsed: 0,
+        }
+    }
+
+    #[test]
+    fn test_baseline_roundtrip() {
+        let mut baseline = Baseline::default();
+        baseline.record(&report(100, &[("P1: Myriad", 2000), ("P1: Skip", 3000)]));
+
+        let parsed = Baseline::parse(&baseline.to_string());
+
+        assert_eq!(parsed.get(4, "Parse"), Some(100));
+        assert_eq!(parsed.get(4, "P1: Myriad"), Some(2000));
+        assert_eq!(parsed.get(4, "P1: Skip"), Some(3000));
+        assert_eq!(parsed.get(5, "Parse"), None);
+    }
+
+    #[test]
+    fn test_baseline_compare() {
+        let mut baseline = Baseline::default();
+        baseline.record(&report(100, &[("P1: Myriad", 2000)]));
+
+        let comparisons = baseline.compare(&report(90, &[("P1: Myriad", 2400), ("P1: Skip", 10)]), 10.0);
+
+        assert_eq!(comparisons.len(), 2);
+        assert_eq!(comparisons[0].label, "Parse");
+        assert_eq!(comparisons[0].change, -10.0);
+        assert!(!comparisons[0].regression);
+        assert_eq!(comparisons[1].label, "P1: Myriad");
+        assert_eq!(comparisons[1].change, 20.0);
+        assert!(comparisons[1].regression);
+    }
+
+    #[test]
+    fn test_verify_report() {
+        let mut report = report(100, &[("P1", 10), ("P2", 20)]);
+        report.parts[0].answer = "12 \n".to_owned();
+        report.parts[1].part = 2;
+        report.parts[1].answer = "7".to_owned();
+
+        let answers = |expected: Option<&'static str>| move |name: &str, part: u32| {
+            assert_eq!(name, "day04");
+            if part == 1 { Some("12".to_owned()) } else { expected.map(|a| a.to_owned()) }
+        };
+
+        assert!(verify_with(&report, answers(Some("7"))).is_empty());
+
+        let mismatches = verify_with(&report, answers(Some("8")));
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].to_string(), "Wrong answer (Day 4, P2): expected 8, got 7");
+
+        let mismatches = verify_with(&report, answers(None));
+        assert_eq!(mismatches.len(), 1);
+        assert!(mismatches[0].is_unrecorded());
+        assert_eq!(mismatches[0].to_string(), "No answer recorded (Day 4, P2)");
+    }
+
+    #[test]
+    fn test_format_records() {
+        let mut report = report(100, &[("P1: Myriad", 2000)]);
+        report.parts[0].answer = "..\n\"x\", y".to_owned();
+
+        let json = format_records(&report, OutputFormat::Json);
+        assert_eq!(json.len(), 3);
+        assert_eq!(json[0], r#"{"day":4,"kind":"result","label":"P1: Myriad","answer":"..\n\"x\", y"}"#);
+        assert!(json[1].starts_with(r#"{"day":4,"kind":"time","label":"Parse","ns":100,"#));
+
+        let csv = format_records(&report, OutputFormat::Csv);
+        assert_eq!(csv[0], "4,result,P1: Myriad,\"..\n\"\"x\"\", y\",,,,,,,");
+        assert_eq!(csv[2], "4,time,P1: Myriad,,2000,0,0,0,0,0,0");
+        assert_eq!(csv[2].split(',').count(), CSV_HEADER.split(',').count());
+
+        assert!(format_records(&report, OutputFormat::Text).is_empty());
+    }
+
+    #[test]
+    fn test_read_input() {
+        assert_eq!(example_name("day10", "example1"), "day10.example1");
+        assert!(input_path("day10").ends_with("day10.txt"));
+
+        let err = read_input("day10", Some("./no/such/input.txt")).unwrap_err();
+        assert!(err.is_not_found());
+        assert!(err.to_string().starts_with("Could not read ./no/such/input.txt: "));
+
+        assert!(read_input("day10", Some("Cargo.toml")).unwrap().contains("[package]"));
     }
-}
\ No newline at end of file
+
+    struct Broken;
+
+    impl Solution for Broken {
+        type Input = u32;
+        type Part1 = u32;
+        type Part2 = u32;
+
+        const DAY: u32 = 19;
+
+        fn parse(input: &str) -> Result<u32, ParseError> {
+            let mut scanner = Scanner::new(input);
+            let n = scanner.unsigned()?;
+            scanner.end()?;
+
+            Ok(n)
+        }
+
+        fn part1(n: &u32) -> u32 {
+            assert!(*n < 10, "{} is too big", n);
+            thread::sleep(Duration::from_millis(*n as u64));
+
+            *n
+        }
+
+        fn part2(n: &u32) -> u32 {
+            loop {
+                thread::sleep(Duration::from_millis(*n as u64));
+            }
+        }
+    }
+
+    #[test]
+    fn test_run_solution_failures() {
+        // No timeout here: printing a backtrace for the panic can take longer than a short one.
+        let report = run_solution::<Broken>("12", &RunOptions { part: Some(1), ..RunOptions::default() });
+        assert_eq!(report.failures(), 1);
+        assert_eq!(report.parts[0].failure, Some(Failure::Panic("12 is too big".to_owned())));
+        assert_eq!(report.timings().len(), 1);
+
+        let options = RunOptions { timeout: Some(Duration::from_millis(50)), ..RunOptions::default() };
+        let report = run_solution::<Broken>("1", &RunOptions { part: Some(2), ..options });
+        assert_eq!(report.failures(), 1);
+        assert_eq!(report.parts[0].answer, "TIMEOUT after 50.00ms");
+        assert_eq!(report.timings().len(), 1);
+
+        let report = run_solution::<Broken>("x", &RunOptions { part: Some(1), ..options });
+        assert!(matches!(report.parse_failure, Some(Failure::Invalid(_))));
+        assert_eq!(report.results()[0].1, "INVALID INPUT at line 1, column 1: expected a number, found 'x'");
+        assert_eq!(report.results()[0].0, "Parse");
+        assert!(report.timings().is_empty());
+    }
+
+    #[test]
+    fn test_timeout_covers_one_run() {
+        let options = RunOptions {
+            part: Some(1),
+            bench: true,
+            min_duration: Duration::from_millis(200),
+            timeout: Some(Duration::from_millis(50)),
+            ..RunOptions::default()
+        };
+
+        let report = run_solution::<Broken>("5", &options);
+        assert_eq!(report.failures(), 0);
+        assert_eq!(report.parts[0].answer, "5");
+        assert!(report.parts[0].stats.runs > 10);
+    }
+
+    #[test]
+    fn test_markdown_report() {
+        let mut solved = report(100, &[("P1: Myriad", 2000), ("P1: Skip", 3000)]);
+        for part in solved.parts.iter_mut() {
+            part.answer = "1929".to_owned();
+        }
+
+        let markdown = markdown_report(&[solved]);
+        let hash = answer_hash("1929\n");
+
+        assert_eq!(hash, answer_hash("1929"));
+        assert!(markdown.starts_with("| Day | Part | Method | Answer hash | Median |\n"));
+        assert!(markdown.contains("| 4 | Parse | | | 100ns |\n"));
+        assert!(markdown.contains(&format!("| 4 | 1 | Myriad | `{}` | 2.0µs |\n", hash)));
+        assert!(markdown.contains("### Day 4, part 1\n"));
+        assert!(markdown.contains(&format!("| Skip | `{}` | 3.0µs | 1.50x |\n", hash)));
+
+        let mut unparsed = report(100, &[("P1: a|b", 2000)]);
+        unparsed.parse_failure = Some(Failure::Panic("left | right\nsecond line\n".to_owned()));
+        let markdown = markdown_report(&[unparsed]);
+
+        assert!(markdown.contains("| 4 | Parse | | PANIC: left \\| right<br>second line | |\n"));
+
+        let mut failed = report(100, &[("P1: a|b", 2000)]);
+        failed.parts[0].failure = Some(Failure::Panic("x".to_owned()));
+        assert!(markdown_report(&[failed]).contains("| 4 | 1 | a\\|b | PANIC |  |\n"));
+    }
+}
@@ -0,0 +1,276 @@
+use std::convert::TryFrom;
+use std::collections::HashMap;
+use std::hash::Hash;
+use crate::grid::{Direction, Grid};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Neighbourhood {
+    /// The four cells next to a cell.
+    Orthogonal,
+    /// The eight cells around a cell, diagonals included.
+    Surrounding,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Cycle {
+    /// The first generation of the state that came back.
+    pub start: usize,
+    pub length: usize,
+}
+
+/// A cellular automaton on a grid that keeps its size. Cells holding anything but the grid's
+/// default value are alive, and everything outside the grid is dead.
+pub struct Automaton<T> {
+    grid: Grid<T>,
+    buffer: Grid<T>,
+    neighbourhood: Neighbourhood,
+    generation: usize,
+}
+
+impl<T> Automaton<T> where T: Clone + Copy + Eq + Hash + std::fmt::Debug {
+    pub fn new(grid: Grid<T>, neighbourhood: Neighbourhood) -> Automaton<T> {
+        Automaton {
+            buffer: grid.clone(),
+            grid,
+            neighbourhood,
+            generation: 0,
+        }
+    }
+
+    pub fn grid(&self) -> &Grid<T> {
+        &self.grid
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn alive(&self) -> usize {
+        let dead = *self.grid.default_value();
+
+        self.grid.data().iter().filter(|v| **v != dead).count()
+    }
+
+    /// Moves one generation ahead. `rule` is given every cell and how many of its neighbours
+    /// are alive, and returns what the cell becomes.
+    pub fn step(&mut self, rule: impl Fn(T, usize) -> T) {
+        let dead = *self.grid.default_value();
+
+        for (x, y, cell) in self.grid.iter() {
+            let alive = match self.neighbourhood {
+                Neighbourhood::Orthogonal => self.grid.neighbours(x, y).filter(|(_, _, v)| *v != dead).count(),
+                Neighbourhood::Surrounding => self.grid.neighbours8(x, y).filter(|(_, _, v)| *v != dead).count(),
+            };
+
+            self.buffer.set(x, y, rule(cell, alive));
+        }
+
+        std::mem::swap(&mut self.grid, &mut self.buffer);
+        self.generation += 1;
+    }
+
+    /// Steps until the grid is in a state it has been in before, and stops there.
+    pub fn find_cycle(&mut self, rule: impl Fn(T, usize) -> T) -> Cycle {
+        let mut seen: HashMap<Vec<T>, usize> = HashMap::new();
+
+        loop {
+            if let Some(start) = seen.insert(self.grid.data().to_vec(), self.generation) {
+                return Cycle { start, length: self.generation - start };
+            }
+
+            self.step(&rule);
+        }
+    }
+
+    /// Steps until `generation`, skipping ahead over whole cycles once one shows up.
+    pub fn run_to(&mut self, generation: usize, rule: impl Fn(T, usize) -> T) {
+        let mut seen: HashMap<Vec<T>, usize> = HashMap::new();
+
+        while self.generation < generation {
+            if let Some(start) = seen.insert(self.grid.data().to_vec(), self.generation) {
+                let remaining = (generation - self.generation) % (self.generation - start);
+                for _ in 0..remaining {
+                    self.step(&rule);
+                }
+
+                self.generation = generation;
+                return;
+            }
+
+            self.step(&rule);
+        }
+    }
+}
+
+/// A stack of square grids where the centre cell of each one holds the whole of the next one
+/// in, so the cells around the centre border an entire edge of the inner grid. New levels are
+/// added on both ends as life spreads to them.
+pub struct RecursiveAutomaton<T> {
+    levels: Vec<Grid<T>>,
+    buffer: Vec<Grid<T>>,
+    empty: Grid<T>,
+    /// The depth of `levels[0]`. Depths grow inwards.
+    outermost: isize,
+    size: isize,
+    generation: usize,
+}
+
+impl<T> RecursiveAutomaton<T> where T: Clone + Copy + Eq + Hash + std::fmt::Debug {
+    /// Starts with `grid` at depth 0. It must be square, with an odd size so it has a centre.
+    pub fn new(grid: &Grid<T>) -> RecursiveAutomaton<T> {
+        let (width, height) = grid.size();
+        assert!(width == height && width % 2 == 1, "recursive grids must be square with an odd size");
+
+        let size = width as isize;
+        let empty = Grid::new(width, height, 0, 0, *grid.default_value());
+        let mut level = empty.clone();
+        for (i, (_, _, v)) in grid.iter().enumerate() {
+            level.set(i as isize % size, i as isize / size, v);
+        }
+        level.set(size / 2, size / 2, *grid.default_value());
+
+        RecursiveAutomaton {
+            levels: vec![level],
+            buffer: vec![empty.clone()],
+            empty,
+            outermost: 0,
+            size,
+            generation: 0,
+        }
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn level(&self, depth: isize) -> Option<&Grid<T>> {
+        usize::try_from(depth - self.outermost).ok().and_then(|i| self.levels.get(i))
+    }
+
+    /// The levels from the outermost in, with their depths.
+    pub fn levels(&self) -> impl Iterator<Item = (isize, &Grid<T>)> + '_ {
+        self.levels.iter().enumerate().map(move |(i, level)| (self.outermost + i as isize, level))
+    }
+
+    pub fn alive(&self) -> usize {
+        self.levels.iter().map(|level| self.alive_in(level)).sum()
+    }
+
+    fn alive_in(&self, level: &Grid<T>) -> usize {
+        let dead = *self.empty.default_value();
+
+        level.data().iter().filter(|v| **v != dead).count()
+    }
+
+    fn is_alive(&self, level: usize, x: isize, y: isize) -> bool {
+        self.levels.get(level).map(|l| l.get(x, y) != *self.empty.default_value()).unwrap_or(false)
+    }
+
+    fn alive_neighbours(&self, level: usize, x: isize, y: isize) -> usize {
+        let centre = self.size / 2;
+        let last = self.size - 1;
+        let mut alive = 0;
+
+        for direction in Direction::ALL.iter() {
+            let (dx, dy) = direction.offset();
+            let (nx, ny) = (x + dx, y + dy);
+
+            if nx < 0 || ny < 0 || nx > last || ny > last {
+                if level > 0 && self.is_alive(level - 1, centre + dx, centre + dy) {
+                    alive += 1;
+                }
+            } else if (nx, ny) == (centre, centre) {
+                // Stepping into the centre reaches the whole facing edge of the inner grid.
+                alive += (0..self.size)
+                    .map(|i| match direction {
+                        Direction::Up => (i, last),
+                        Direction::Right => (0, i),
+                        Direction::Down => (i, 0),
+                        Direction::Left => (last, i),
+                    })
+                    .filter(|(ix, iy)| self.is_alive(level + 1, *ix, *iy))
+                    .count();
+            } else if self.is_alive(level, nx, ny) {
+                alive += 1;
+            }
+        }
+
+        alive
+    }
+
+    /// Moves one generation ahead, like `Automaton::step`.
+    pub fn step(&mut self, rule: impl Fn(T, usize) -> T) {
+        if self.alive_in(&self.levels[0]) > 0 {
+            self.levels.insert(0, self.empty.clone());
+            self.outermost -= 1;
+        }
+        if self.alive_in(self.levels.last().unwrap()) > 0 {
+            self.levels.push(self.empty.clone());
+        }
+        self.buffer.resize(self.levels.len(), self.empty.clone());
+
+        let centre = self.size / 2;
+        for level in 0..self.levels.len() {
+            for y in 0..self.size {
+                for x in 0..self.size {
+                    if (x, y) == (centre, centre) {
+                        continue;
+                    }
+
+                    let next = rule(self.levels[level].get(x, y), self.alive_neighbours(level, x, y));
+                    self.buffer[level].set(x, y, next);
+                }
+            }
+        }
+
+        std::mem::swap(&mut self.levels, &mut self.buffer);
+        self.generation += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUGS: &str = "....#\n#..#.\n#..##\n..#..\n#....\n";
+
+    fn bug_rule(cell: char, alive: usize) -> char {
+        match (cell, alive) {
+            ('#', 1) => '#',
+            ('.', 1) | ('.', 2) => '#',
+            _ => '.',
+        }
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let mut automaton = Automaton::new(Grid::from_str_lines(BUGS, '.').unwrap(), Neighbourhood::Orthogonal);
+        automaton.step(bug_rule);
+        assert_eq!(automaton.grid().to_string(), "#..#.\n####.\n###.#\n##.##\n.##..\n");
+
+        let cycle = automaton.find_cycle(bug_rule);
+        let biodiversity: u32 = automaton.grid().positions(|c| c == '#').map(|(x, y)| 1 << (y * 5 + x)).sum();
+        assert_eq!(biodiversity, 2129920);
+        assert_eq!(cycle.start + cycle.length, automaton.generation());
+
+        let mut skipped = Automaton::new(Grid::from_str_lines(BUGS, '.').unwrap(), Neighbourhood::Orthogonal);
+        skipped.run_to(cycle.start + cycle.length * 1000 + 3, bug_rule);
+        let mut stepped = Automaton::new(Grid::from_str_lines(BUGS, '.').unwrap(), Neighbourhood::Orthogonal);
+        for _ in 0..cycle.start + 3 {
+            stepped.step(bug_rule);
+        }
+        assert_eq!(skipped.grid().data(), stepped.grid().data());
+    }
+
+    #[test]
+    fn test_recursive() {
+        let mut automaton = RecursiveAutomaton::new(&Grid::from_str_lines(BUGS, '.').unwrap());
+        for _ in 0..10 {
+            automaton.step(bug_rule);
+        }
+
+        assert_eq!(automaton.alive(), 99);
+        assert_eq!(automaton.level(-5).unwrap().to_string(), "..#..\n.#.#.\n....#\n.#.#.\n..#..\n");
+        assert_eq!(automaton.level(5).unwrap().to_string(), "####.\n#..#.\n#..#.\n####.\n.....\n");
+    }
+}
@@ -0,0 +1,167 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// Calls faster than this are timed in batches, since a single call would mostly measure the
+/// clock itself.
+const MIN_SAMPLE_NS: u128 = 1_000;
+
+#[derive(Clone, Copy, Debug)]
+pub struct BenchOptions {
+    pub iterations: usize,
+    pub warmup: Duration,
+    pub min_duration: Duration,
+}
+
+impl BenchOptions {
+    pub fn iterations(iterations: usize) -> BenchOptions {
+        BenchOptions {
+            iterations,
+            warmup: Duration::from_secs(0),
+            min_duration: Duration::from_secs(0),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Stats {
+    pub runs: usize,
+    pub mean: i64,
+    pub min: i64,
+    pub median: i64,
+    pub p95: i64,
+    pub stddev: i64,
+    pub outliers: usize,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &mut [i64], runs: usize) -> Stats {
+        if samples.is_empty() {
+            return Stats::default();
+        }
+
+        samples.sort_unstable();
+
+        let len = samples.len();
+        let mean = samples.iter().sum::<i64>() / len as i64;
+        let variance = samples.iter()
+            .map(|s| ((s - mean) as f64).powi(2))
+            .sum::<f64>() / len as f64;
+
+        // Tukey's fences: anything further than 1.5 IQR outside the middle half is an outlier.
+        let q1 = percentile(samples, 25);
+        let q3 = percentile(samples, 75);
+        let fence = (q3 - q1) * 3 / 2;
+        let outliers = samples.iter()
+            .filter(|s| **s < q1 - fence || **s > q3 + fence)
+            .count();
+
+        Stats {
+            runs, mean, outliers,
+            min: samples[0],
+            median: percentile(samples, 50),
+            p95: percentile(samples, 95),
+            stddev: variance.sqrt() as i64,
+        }
+    }
+}
+
+fn percentile(sorted: &[i64], p: usize) -> i64 {
+    let rank = (sorted.len() * p).div_ceil(100);
+
+    sorted[rank.max(1) - 1]
+}
+
+/// Runs the callback until it has been called at least `iterations` times and `min_duration`
+/// has passed, after warming up for `warmup`. The result of the last call is returned.
+pub fn bench<T>(options: &BenchOptions, mut callback: impl FnMut() -> T) -> (T, Stats) {
+    // Without a warmup, the batch size is estimated from the first (cold) call instead.
+    let mut batch = 0;
+    if options.warmup > Duration::from_secs(0) {
+        let start = Instant::now();
+        let mut calls = 0;
+        while start.elapsed() < options.warmup {
+            black_box(callback());
+            calls += 1;
+        }
+
+        batch = batch_size(start.elapsed().as_nanos() / calls);
+    }
+
+    let start = Instant::now();
+    let mut samples: Vec<i64> = Vec::with_capacity(options.iterations.clamp(1, 1 << 16));
+    let mut result = None;
+    let mut runs = 0;
+
+    while runs == 0 || runs < options.iterations || start.elapsed() < options.min_duration {
+        let count = if batch == 0 {
+            1
+        } else if runs < options.iterations {
+            batch.min(options.iterations - runs)
+        } else {
+            batch
+        };
+
+        let sample_start = Instant::now();
+        for _ in 0..count {
+            result = Some(black_box(callback()));
+        }
+        let sample_ns = sample_start.elapsed().as_nanos() / count as u128;
+
+        if batch == 0 {
+            batch = batch_size(sample_ns);
+        }
+
+        samples.push(sample_ns as i64);
+        runs += count;
+    }
+
+    (result.unwrap(), Stats::from_samples(&mut samples, runs))
+}
+
+fn batch_size(call_ns: u128) -> usize {
+    (MIN_SAMPLE_NS / call_ns.max(1)).max(1) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_from_samples() {
+        let mut samples = vec![14, 10, 12, 11, 13, 10, 12, 11, 100, 12];
+        let stats = Stats::from_samples(&mut samples, 10);
+
+        assert_eq!(stats.runs, 10);
+        assert_eq!(stats.min, 10);
+        assert_eq!(stats.median, 12);
+        assert_eq!(stats.p95, 100);
+        assert_eq!(stats.mean, 20);
+        assert_eq!(stats.outliers, 1);
+    }
+
+    #[test]
+    fn test_bench_iterations() {
+        let mut calls = 0;
+        let (result, stats) = bench(&BenchOptions::iterations(500), || {
+            calls += 1;
+            calls
+        });
+
+        assert_eq!(result, 500);
+        assert_eq!(stats.runs, 500);
+    }
+
+    #[test]
+    fn test_bench_min_duration() {
+        let options = BenchOptions {
+            iterations: 1,
+            warmup: Duration::from_millis(1),
+            min_duration: Duration::from_millis(5),
+        };
+        let start = Instant::now();
+        let (_, stats) = bench(&options, || (0..100).sum::<u64>());
+
+        assert!(start.elapsed() >= Duration::from_millis(6));
+        assert!(stats.runs > 1);
+    }
+}
@@ -0,0 +1,129 @@
+use crate::aoc::Solution;
+use crate::parse::{ParseError, Scanner};
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input = Vec<u32>;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    const DAY: u32 = 1;
+    const PARSE_ITERATIONS: usize = 10000;
+    const PART1_ITERATIONS: usize = 100000;
+    const PART2_ITERATIONS: usize = 100000;
+
+    fn parse(input: &str) -> Result<Vec<u32>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(list: &Vec<u32>) -> u32 {
+        part1(list)
+    }
+
+    fn part2(list: &Vec<u32>) -> u32 {
+        part2(list)
+    }
+}
+
+fn parse_input(input: &str) -> Result<Vec<u32>, ParseError> {
+    let mut list: Vec<u32> = Vec::with_capacity(128);
+    let mut scanner = Scanner::new(input);
+
+    scanner.skip_whitespace();
+    while !scanner.is_done() {
+        list.push(scanner.unsigned()?);
+        scanner.newline()?;
+        scanner.skip_whitespace();
+    }
+
+    Ok(list)
+}
+
+fn part1(list: &[u32]) -> u32 {
+    let mut total: u32 = 0;
+    for item in list.iter() {
+        total += calc_fuel_required(*item)
+    }
+
+    total
+}
+
+fn part2(list: &[u32]) -> u32 {
+    let mut total: u32 = 0;
+    for item in list.iter() {
+        total += calc_fuel_required_re(*item)
+    }
+
+    total
+}
+
+fn calc_fuel_required(mass: u32) -> u32 {
+    (mass / 3) - 2
+}
+
+fn calc_fuel_required_re(mass: u32) -> u32 {
+    let mut fuel = (mass / 3) - 2;
+    let mut fuel_accounted_for: u32 = 0;
+
+    loop {
+        let fuel_diff = fuel - fuel_accounted_for;
+        if fuel_diff < 6 {
+            break
+        }
+
+        fuel_accounted_for = fuel;
+        fuel += (fuel_diff / 3) - 2;
+    }
+
+    fuel
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::assert_scanner_speed;
+
+    #[test]
+    fn test_calc_fuel_required() {
+        assert_eq!(calc_fuel_required(12), 2);
+        assert_eq!(calc_fuel_required(14), 2);
+        assert_eq!(calc_fuel_required(1969), 654);
+        assert_eq!(calc_fuel_required(100756), 33583);
+    }
+
+    #[test]
+    fn test_calc_fuel_required_re() {
+        assert_eq!(calc_fuel_required_re(14), 2);
+        assert_eq!(calc_fuel_required_re(1969), 966);
+        assert_eq!(calc_fuel_required_re(100756), 50346);
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_scanner() {
+        fn hand_rolled(input: &str) -> Vec<u32> {
+            let mut list: Vec<u32> = Vec::with_capacity(128);
+            let mut sum: u32 = 0;
+
+            for ch in input.chars() {
+                if ch == '\n' || ch == '\r' {
+                    if sum > 0 {
+                        list.push(sum);
+                    }
+                    sum = 0;
+                    continue
+                }
+
+                sum *= 10;
+                sum += ch as u32 - '0' as u32;
+            }
+
+            list
+        }
+
+        let input: String = (1..5000u32).map(|n| format!("{}\n", n * 7919 % 100000 + 1)).collect();
+
+        assert_scanner_speed(|| hand_rolled(&input), || parse_input(&input).unwrap());
+    }
+}
@@ -1,46 +1,45 @@
-use common::aoc::{load_input, run_many, print_result, print_time};
-
-fn main() {
-    let input = load_input("day02");
-
-    let (program, dur_parse) = run_many(10000, || parse_input(&input));
-    let (res_part1, dur_part1) = run_many(10000, || part1(&program));
-    let (res_part2, dur_part2) = run_many(10000, || part2(&program));
-    let (res_part2_bs, dur_part2_bs) = run_many(10000, || part2_bs(&program));
-    let (res_part2_reduce, dur_part2_reduce) = run_many(10000, || part2_reduce(&program));
-
-    print_result("P1", res_part1);
-    print_result("P2: Pattern Exploit", res_part2);
-    print_result("P2: Binary Search", res_part2_bs);
-    print_result("P2: Reduce [not my idea]", res_part2_reduce);
-    print_time("Parse", dur_parse);
-    print_time("P1", dur_part1);
-    print_time("P2: Pattern Exploit", dur_part2);
-    print_time("P2: Binary Search", dur_part2_bs);
-    print_time("P2: Reduce [not my idea]", dur_part2_reduce);
-}
+use crate::aoc::{Solution, Method};
+use crate::parse::{ParseError, Scanner};
 
-fn parse_input(input: &str) -> Vec<u32> {
-    let mut result: Vec<u32> = Vec::with_capacity(input.len() / 2);
-    let mut next: u32 = 0;
-    let zero_char = '0' as u32;
+pub struct Day02;
 
-    for ch in input.chars() {
-        if ch == '\r' || ch == '\n' {
-            continue;
-        } else if ch == ',' {
-            result.push(next);
-            next = 0;
-            continue;
-        }
+impl Solution for Day02 {
+    type Input = Vec<u32>;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    const DAY: u32 = 2;
+    const PARSE_ITERATIONS: usize = 10000;
+    const PART1_ITERATIONS: usize = 10000;
+    const PART2_ITERATIONS: usize = 10000;
+    const PART2_METHOD: &'static str = "Pattern Exploit";
+
+    fn parse(input: &str) -> Result<Vec<u32>, ParseError> {
+        parse_input(input)
+    }
 
-        next *= 10;
-        next += (ch as u32) - zero_char;
+    fn part1(program: &Vec<u32>) -> u32 {
+        part1(program)
     }
 
-    result.push(next);
+    fn part2(program: &Vec<u32>) -> u32 {
+        part2(program)
+    }
+
+    fn part2_alternatives() -> Vec<Method<Vec<u32>, u32>> {
+        vec![
+            ("Binary Search", |program| part2_bs(program)),
+            ("Reduce [not my idea]", |program| part2_reduce(program)),
+        ]
+    }
+}
+
+fn parse_input(input: &str) -> Result<Vec<u32>, ParseError> {
+    let mut scanner = Scanner::new(input);
+    let program = scanner.separated(b',', |s| s.unsigned())?;
+    scanner.end()?;
 
-    result
+    Ok(program)
 }
 
 fn part1(initial_program: &[u32]) -> u32 {
@@ -137,7 +136,7 @@ fn part2_reduce(initial_program: &[u32]) -> u32 {
 }
 
 
-fn run_intcode(program: &mut Vec<u32>) {
+fn run_intcode(program: &mut [u32]) {
     let mut position: usize = 0;
 
     loop {
@@ -173,6 +172,7 @@ fn run_intcode(program: &mut Vec<u32>) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parse::assert_scanner_speed;
 
     #[test]
     fn test_run_intcode() {
@@ -196,4 +196,33 @@ mod tests {
         run_intcode(&mut program);
         assert_eq!(program, expected);
     }
-}
\ No newline at end of file
+
+    #[test]
+    #[ignore]
+    fn bench_scanner() {
+        fn hand_rolled(input: &str) -> Vec<u32> {
+            let mut result: Vec<u32> = Vec::with_capacity(input.len() / 2);
+            let mut next: u32 = 0;
+
+            for ch in input.chars() {
+                if ch == '\r' || ch == '\n' {
+                    continue;
+                } else if ch == ',' {
+                    result.push(next);
+                    next = 0;
+                    continue;
+                }
+
+                next *= 10;
+                next += (ch as u32) - '0' as u32;
+            }
+            result.push(next);
+
+            result
+        }
+
+        let input = (0..5000u32).map(|n| (n * 7919 % 100000).to_string()).collect::<Vec<_>>().join(",") + "\n";
+
+        assert_scanner_speed(|| hand_rolled(&input), || parse_input(&input).unwrap());
+    }
+}
@@ -1,39 +1,48 @@
-use common::aoc::{load_input, run_many, print_time, print_result};
+use crate::aoc::Solution;
+use crate::parse::{ParseError, Scanner};
 
-fn main() {
-    let input = load_input("day03");
+pub struct Day03;
 
-    let (wires, dur_parse) = run_many(1000, || parse_input(&input));
-    let w1 = &wires[0];
-    let w2 = &wires[1];
+impl Solution for Day03 {
+    type Input = Vec<Wire>;
+    type Part1 = i32;
+    type Part2 = i32;
 
-    let (res_part1, dur_part1) = run_many(1000, || w1.closest_intersection(&w2));
-    let (res_part2, dur_part2) = run_many(1000, || w1.shortest_intersection(&w2));
+    const DAY: u32 = 3;
 
-    print_result("P1", res_part1.unwrap_or_default());
-    print_result("P2", res_part2.unwrap_or_default());
+    fn parse(input: &str) -> Result<Vec<Wire>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(wires: &Vec<Wire>) -> i32 {
+        wires[0].closest_intersection(&wires[1]).unwrap_or_default()
+    }
 
-    print_time("Parse", dur_parse);
-    print_time("P1", dur_part1);
-    print_time("P2", dur_part2);
+    fn part2(wires: &Vec<Wire>) -> i32 {
+        wires[0].shortest_intersection(&wires[1]).unwrap_or_default()
+    }
 }
 
-fn parse_input(input: &str) -> Vec<Wire> {
+fn parse_input(input: &str) -> Result<Vec<Wire>, ParseError> {
     let mut results = Vec::with_capacity(2);
 
-    for line in input.lines() {
-        if line.len() < 1 {
+    for (i, line) in input.lines().enumerate() {
+        if line.is_empty() {
             continue;
         }
 
-        results.push(Wire::parse(line));
+        results.push(Wire::parse(line).map_err(|e| e.on_line(i + 1))?);
     }
 
-    results
+    if results.len() != 2 {
+        return Err(Scanner::new(input).error_at_end(format!("expected 2 wires, found {}", results.len())));
+    }
+
+    Ok(results)
 }
 
 #[derive(Eq, PartialEq, Clone, Debug)]
-struct Wire {
+pub struct Wire {
     points: Vec<Point>,
 }
 
@@ -87,8 +96,8 @@ impl Wire {
 
                 if let Some(intersect) = line.intersects(&other_line) {
                     if intersect.x != 0 || intersect.y != 0 {
-                        let line_len2 = line_len + Line(&prev, &intersect).len() - 1;
-                        let other_len2 = other_len + Line(&other_prev, &intersect).len() - 1;
+                        let line_len2 = line_len + Line(prev, &intersect).len() - 1;
+                        let other_len2 = other_len + Line(other_prev, &intersect).len() - 1;
                         let total_len = line_len2 + other_len2;
 
                         if let Some(winner_len) = winner {
@@ -126,24 +135,32 @@ impl Wire {
         winner
     }
 
-    fn parse(str: &str) -> Wire {
+    fn parse(str: &str) -> Result<Wire, ParseError> {
         let mut wire = Wire {
             points: Vec::with_capacity(str.len() / 3),
         };
+        let mut scanner = Scanner::new(str);
 
         let mut current = Point::new(0, 0);
         wire.points.push(current.clone());
 
-        for token in str.split(',') {
-            let dir: char = token.chars().next().unwrap();
-            let len_str: &str = &token[1..];
-            let len: i32 = len_str.parse().unwrap();
+        loop {
+            let dir = match scanner.peek() {
+                Some(b'L') | Some(b'R') | Some(b'U') | Some(b'D') => scanner.take().unwrap() as char,
+                _ => return Err(scanner.unexpected("L, R, U or D")),
+            };
+            let len: i32 = scanner.unsigned()?;
 
             current = current.next(dir, len);
             wire.points.push(current.clone());
+
+            if !scanner.accept(b',') {
+                break;
+            }
         }
+        scanner.end()?;
 
-        wire
+        Ok(wire)
     }
 }
 
@@ -217,7 +234,7 @@ mod tests {
 
     #[test]
     fn test_wire_parse() {
-        let actual = Wire::parse("U32,R64,D48,L96,U20");
+        let actual = Wire::parse("U32,R64,D48,L96,U20").unwrap();
         let expected = Wire {
             points: vec! {
                 Point { x: 0, y: 0 },
@@ -234,10 +251,10 @@ mod tests {
 
     #[test]
     fn test_wire_closest_intersection() {
-        let w1 = Wire::parse("R75,D30,R83,U83,L12,D49,R71,U7,L72");
-        let w2 = Wire::parse("U62,R66,U55,R34,D71,R55,D58,R83");
-        let w3 = Wire::parse("R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51");
-        let w4 = Wire::parse("U98,R91,D20,R16,D67,R40,U7,R15,U6,R7");
+        let w1 = Wire::parse("R75,D30,R83,U83,L12,D49,R71,U7,L72").unwrap();
+        let w2 = Wire::parse("U62,R66,U55,R34,D71,R55,D58,R83").unwrap();
+        let w3 = Wire::parse("R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51").unwrap();
+        let w4 = Wire::parse("U98,R91,D20,R16,D67,R40,U7,R15,U6,R7").unwrap();
 
         assert_eq!(w1.closest_intersection(&w2), Some(159));
         assert_eq!(w3.closest_intersection(&w4), Some(135));
@@ -245,12 +262,12 @@ mod tests {
 
     #[test]
     fn test_wire_shortest_intersection() {
-        let w1 = Wire::parse("R75,D30,R83,U83,L12,D49,R71,U7,L72");
-        let w2 = Wire::parse("U62,R66,U55,R34,D71,R55,D58,R83");
-        let w3 = Wire::parse("R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51");
-        let w4 = Wire::parse("U98,R91,D20,R16,D67,R40,U7,R15,U6,R7");
-        let w5 = Wire::parse("R8,U5,L5,D3");
-        let w6 = Wire::parse("U7,R6,D4,L4");
+        let w1 = Wire::parse("R75,D30,R83,U83,L12,D49,R71,U7,L72").unwrap();
+        let w2 = Wire::parse("U62,R66,U55,R34,D71,R55,D58,R83").unwrap();
+        let w3 = Wire::parse("R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51").unwrap();
+        let w4 = Wire::parse("U98,R91,D20,R16,D67,R40,U7,R15,U6,R7").unwrap();
+        let w5 = Wire::parse("R8,U5,L5,D3").unwrap();
+        let w6 = Wire::parse("U7,R6,D4,L4").unwrap();
 
         assert_eq!(w5.shortest_intersection(&w6), Some(30));
         assert_eq!(w1.shortest_intersection(&w2), Some(610));
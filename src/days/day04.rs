@@ -1,30 +1,45 @@
-use common::aoc::{load_input, run_many, print_time, print_result};
-
-fn main() {
-    let input = load_input("day04");
-
-    let ((start, end), dur_parse) = run_many(10000, || parse_input(&input));
-    let (res_part1_bf, dur_part1_bf) = run_many(100, || part1_bf(start, end));
-    let (res_part1_skip, dur_part1_skip) = run_many(100, || part1_skip(start, end));
-    let (res_part1_myriad, dur_part1_myriad) = run_many(100, || part1_myriad(start, end));
-    let (res_part2_bf, dur_part2_bf) = run_many(100, || part2_bf(start, end));
-    let (res_part2_skip, dur_part2_skip) = run_many(100, || part2_skip(start, end));
-    let (res_part2_myriad, dur_part2_myriad) = run_many(100, || part2_myriad(start, end));
-
-    print_result("P1: Brute Force", res_part1_bf);
-    print_result("P1: Skip", res_part1_skip);
-    print_result("P1: Myriad", res_part1_myriad);
-    print_result("P2: Brute Force", res_part2_bf);
-    print_result("P2: Skip", res_part2_skip);
-    print_result("P2: Myriad", res_part2_myriad);
-
-    print_time("Parse", dur_parse);
-    print_time("P1: Brute Force", dur_part1_bf);
-    print_time("P1: Skip", dur_part1_skip);
-    print_time("P1: Myriad", dur_part1_myriad);
-    print_time("P2: Brute Force", dur_part2_bf);
-    print_time("P2: Skip", dur_part2_skip);
-    print_time("P2: Myriad", dur_part2_myriad);
+use crate::aoc::{Solution, Method};
+use crate::parse::{ParseError, Scanner};
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Input = (u32, u32);
+    type Part1 = u32;
+    type Part2 = u32;
+
+    const DAY: u32 = 4;
+    const PARSE_ITERATIONS: usize = 10000;
+    const PART1_ITERATIONS: usize = 100;
+    const PART2_ITERATIONS: usize = 100;
+    const PART1_METHOD: &'static str = "Myriad";
+    const PART2_METHOD: &'static str = "Myriad";
+
+    fn parse(input: &str) -> Result<(u32, u32), ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(&(start, end): &(u32, u32)) -> u32 {
+        part1_myriad(start, end)
+    }
+
+    fn part2(&(start, end): &(u32, u32)) -> u32 {
+        part2_myriad(start, end)
+    }
+
+    fn part1_alternatives() -> Vec<Method<(u32, u32), u32>> {
+        vec![
+            ("Brute Force", |&(start, end)| part1_bf(start, end)),
+            ("Skip", |&(start, end)| part1_skip(start, end)),
+        ]
+    }
+
+    fn part2_alternatives() -> Vec<Method<(u32, u32), u32>> {
+        vec![
+            ("Brute Force", |&(start, end)| part2_bf(start, end)),
+            ("Skip", |&(start, end)| part2_skip(start, end)),
+        ]
+    }
 }
 
 fn part1_bf(start: u32, end: u32) -> u32 {
@@ -148,12 +163,14 @@ fn part2_myriad(start: u32, end: u32) -> u32 {
     total
 }
 
-fn parse_input(input: &str) -> (u32, u32) {
-    let mut iter = input.trim_end().split('-');
-    let a: u32 = iter.next().unwrap().parse().unwrap();
-    let b: u32 = iter.next().unwrap().parse().unwrap();
+fn parse_input(input: &str) -> Result<(u32, u32), ParseError> {
+    let mut scanner = Scanner::new(input);
+    let a = scanner.unsigned()?;
+    scanner.expect(b'-')?;
+    let b = scanner.unsigned()?;
+    scanner.end()?;
 
-    (a, b)
+    Ok((a, b))
 }
 
 fn password_valid(n: u32) -> bool {
@@ -190,7 +207,7 @@ fn password_valid_skip(n: u32) -> (bool, u32) {
         let curr_digit = n % 10;
         if curr_digit > prev_digit {
             if skip > 1 {
-                skip = skip / 10;
+                skip /= 10;
             }
 
             return (false, skip);
@@ -256,7 +273,7 @@ fn password_valid_p2_skip(n: u32) -> (bool, u32) {
         let curr_digit = n % 10;
         if curr_digit > prev_digit {
             if skip > 1 {
-                skip = skip / 10;
+                skip /= 10;
             }
 
             return (false, skip);
@@ -286,6 +303,7 @@ fn password_valid_p2_skip(n: u32) -> (bool, u32) {
 
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
 
@@ -314,4 +332,4 @@ mod tests {
         assert_eq!(password_valid_p2(114555), true);
         assert_eq!(password_valid_p2(112345), true);
     }
-}
\ No newline at end of file
+}
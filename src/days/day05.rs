@@ -0,0 +1,42 @@
+use crate::aoc::Solution;
+use crate::parse::ParseError;
+use crate::intcode::VM;
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Input = VM;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    const DAY: u32 = 5;
+    const PARSE_ITERATIONS: usize = 10000;
+    const PART1_ITERATIONS: usize = 10000;
+    const PART2_ITERATIONS: usize = 10000;
+
+    fn parse(input: &str) -> Result<VM, ParseError> {
+        VM::parse(input)
+    }
+
+    fn part1(vm: &VM) -> i64 {
+        part1(&mut vm.clone())
+    }
+
+    fn part2(vm: &VM) -> i64 {
+        part2(&mut vm.clone())
+    }
+}
+
+fn part1(vm: &mut VM) -> i64 {
+    vm.reset();
+    vm.push_input(1);
+    vm.run();
+    *vm.output().last().unwrap()
+}
+
+fn part2(vm: &mut VM) -> i64 {
+    vm.reset();
+    vm.push_input(5);
+    vm.run();
+    *vm.output().last().unwrap()
+}
\ No newline at end of file
@@ -1,22 +1,30 @@
 use std::collections::BTreeMap;
-use common::aoc::{load_input, run_many, print_time, print_result};
+use crate::aoc::Solution;
+use crate::parse::{ParseError, Scanner};
 
-fn main() {
-    let input = load_input("day06");
+pub struct Day06;
 
-    let (set, dur_parse) = run_many(1000, || OrbiterSet::parse(&input));
-    let (res_part1, dur_part1) = run_many(1000, || set.checksum());
-    let (res_part2, dur_part2) = run_many(1000, || set.num_transfers("YOU", "SAN"));
+impl Solution for Day06 {
+    type Input = OrbiterSet;
+    type Part1 = u32;
+    type Part2 = u32;
 
-    print_result("P1", res_part1);
-    print_result("P2", res_part2);
+    const DAY: u32 = 6;
 
-    print_time("Parse", dur_parse);
-    print_time("P1", dur_part1);
-    print_time("P2", dur_part2);
+    fn parse(input: &str) -> Result<OrbiterSet, ParseError> {
+        OrbiterSet::parse(input)
+    }
+
+    fn part1(set: &OrbiterSet) -> u32 {
+        set.checksum()
+    }
+
+    fn part2(set: &OrbiterSet) -> u32 {
+        set.num_transfers("YOU", "SAN")
+    }
 }
 
-struct OrbiterSet {
+pub struct OrbiterSet {
     list: Vec<Orbiter>,
     map: BTreeMap<u32, usize>,
 }
@@ -134,17 +142,17 @@ impl OrbiterSet {
         }
     }
 
-    fn parse(str: &str) -> OrbiterSet {
+    fn parse(str: &str) -> Result<OrbiterSet, ParseError> {
         let mut om = Self::new();
+        let mut scanner = Scanner::new(str);
 
-        for line in str.lines() {
-            if line.len() < 2 {
-                continue;
-            }
-
-            let mut tokens = line.split(')');
-            let left: &str = tokens.next().unwrap();
-            let right: &str = tokens.next().unwrap();
+        scanner.skip_whitespace();
+        while !scanner.is_done() {
+            let left = parse_name(&mut scanner)?;
+            scanner.expect(b')')?;
+            let right = parse_name(&mut scanner)?;
+            scanner.newline()?;
+            scanner.skip_whitespace();
 
             let left_index = om.ensure_orbiter(left);
             let right_index = om.ensure_orbiter(right);
@@ -152,10 +160,21 @@ impl OrbiterSet {
             om.list[right_index].parent = left_index;
         }
 
-        let com_index = om.find_orbiter("COM");
+        let com_index = match om.map.get(&str_to_key("COM")) {
+            Some(index) => *index,
+            None => return Err(scanner.error_at_end("COM is not in the map")),
+        };
         om.list[com_index].parent = com_index;
 
-        om
+        Ok(om)
+    }
+}
+
+fn parse_name<'a>(scanner: &mut Scanner<'a>) -> Result<&'a str, ParseError> {
+    let name = scanner.ident()?;
+    match name.len() {
+        1..=3 => Ok(name),
+        _ => Err(scanner.error(format!("{} is longer than 3 characters", name))),
     }
 }
 
@@ -198,14 +217,14 @@ mod tests {
 
     #[test]
     fn test_checksum() {
-        let set = OrbiterSet::parse(INPUT_STR_P1);
+        let set = OrbiterSet::parse(INPUT_STR_P1).unwrap();
 
         assert_eq!(set.checksum(), 42);
     }
 
     #[test]
     fn test_num_transfers() {
-        let set = OrbiterSet::parse(INPUT_STR_P2);
+        let set = OrbiterSet::parse(INPUT_STR_P2).unwrap();
 
         assert_eq!(set.num_transfers("YOU", "SAN"), 4);
     }
@@ -1,23 +1,30 @@
-use common::aoc::{load_input, run_many, print_time, print_result, print_result_multiline};
+use crate::aoc::Solution;
+use crate::grid::Grid;
+use crate::parse::{ParseError, Scanner};
 
-fn main() {
-    let input = load_input("day08");
+pub struct Day08;
 
-    let (image, dur_parse) = run_many(1000, || Image::parse(&input, 25, 6));
-    let (res_part1, dur_part1) = run_many(1000, || image.best_layer_checksum());
-    let (res_part2, dur_part2) = run_many(1000, || image.render());
+impl Solution for Day08 {
+    type Input = Image;
+    type Part1 = usize;
+    type Part2 = String;
 
-    print_result("P1", res_part1);
-    print_result_multiline("P2", res_part2);
+    const DAY: u32 = 8;
 
-    print_time("Parse", dur_parse);
-    print_time("P1", dur_part1);
-    print_time("P2", dur_part2);
-}
+    fn parse(input: &str) -> Result<Image, ParseError> {
+        Image::parse(input, 25, 6)
+    }
+
+    fn part1(image: &Image) -> usize {
+        image.best_layer_checksum()
+    }
 
-const ZERO_BYTE: i32 = '0' as i32;
+    fn part2(image: &Image) -> String {
+        image.render()
+    }
+}
 
-struct Image {
+pub struct Image {
     width: usize,
     height: usize,
     layers: Vec<Vec<i32>>,
@@ -59,23 +66,15 @@ impl Image {
             }
         }
 
-        let mut result = String::with_capacity((self.width * self.height) + self.height);
-
-        for i in 0..self.height {
-            let pos = i * self.width;
-            for pixel in &render[pos..pos + self.width] {
-                result.push(match *pixel {
-                    0 => '.',
-                    1 => '#',
-                    2 => ' ',
-                    _ => panic!("invalid pixel {}", pixel),
-                });
-            }
-
-            result.push('\n');
-        }
+        let mut grid = Grid::new(self.width, self.height, 0, 0, 2);
+        grid.set_data(&render);
 
-        result
+        grid.render(|pixel| match pixel {
+            0 => '.',
+            1 => '#',
+            2 => ' ',
+            _ => panic!("invalid pixel {}", pixel),
+        })
     }
 
     fn new(width: usize, height: usize) -> Image {
@@ -85,13 +84,14 @@ impl Image {
         }
     }
 
-    fn parse(str: &str, width: usize, height: usize) -> Image {
+    fn parse(str: &str, width: usize, height: usize) -> Result<Image, ParseError> {
         let mut image = Self::new(width, height);
         let pixel_count = width * height;
+        let mut scanner = Scanner::new(str);
 
         let mut current_layer: Vec<i32> = Vec::with_capacity(width * height);
-        for ch in str.chars() {
-            current_layer.push(ch as i32 - ZERO_BYTE);
+        while let Some(b'0'..=b'9') = scanner.peek() {
+            current_layer.push(scanner.digit()? as i32);
 
             if current_layer.len() == pixel_count {
                 image.add_layer(&current_layer);
@@ -99,6 +99,11 @@ impl Image {
             }
         }
 
-        image
+        if !current_layer.is_empty() {
+            return Err(scanner.error(format!("the last layer has {} of {} pixels", current_layer.len(), pixel_count)));
+        }
+        scanner.end()?;
+
+        Ok(image)
     }
 }
\ No newline at end of file
@@ -0,0 +1,46 @@
+use crate::aoc::Solution;
+use crate::parse::ParseError;
+use crate::intcode::VM;
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    type Input = VM;
+    type Part1 = i64;
+    type Part2 = i64;
+
+    const DAY: u32 = 9;
+    const PARSE_ITERATIONS: usize = 10000;
+    const PART1_ITERATIONS: usize = 10000;
+    const PART2_ITERATIONS: usize = 300;
+
+    fn parse(input: &str) -> Result<VM, ParseError> {
+        VM::parse(input)
+    }
+
+    fn part1(vm: &VM) -> i64 {
+        part1(&mut vm.clone())
+    }
+
+    fn part2(vm: &VM) -> i64 {
+        part2(&mut vm.clone())
+    }
+}
+
+fn part1(vm: &mut VM) -> i64 {
+    vm.push_input(1);
+    vm.run();
+
+    if vm.output().len() > 1 {
+        panic!("[BOOST] check opcode: {:?}", vm.output())
+    }
+
+    *vm.output().first().unwrap()
+}
+
+fn part2(vm: &mut VM) -> i64 {
+    vm.push_input(2);
+    vm.run();
+
+    *vm.output().first().unwrap()
+}
\ No newline at end of file
@@ -0,0 +1,117 @@
+use crate::aoc::Solution;
+use crate::parse::ParseError;
+use crate::grid::{Grid, count_visible, rotation_order};
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = AsteroidField;
+    type Part1 = u32;
+    type Part2 = String;
+
+    const DAY: u32 = 10;
+    const PART1_ITERATIONS: usize = 100;
+    const PART2_ITERATIONS: usize = 100;
+
+    fn parse(input: &str) -> Result<AsteroidField, ParseError> {
+        AsteroidField::parse(input)
+    }
+
+    fn part1(field: &AsteroidField) -> u32 {
+        let (_, _, score) = field.find_location();
+
+        score
+    }
+
+    fn part2(field: &AsteroidField) -> String {
+        let (x, y, _) = field.find_location();
+        match field.destroy_asteroids(x, y, 200) {
+            Ok((x2, y2)) => format!("{},{}", x2, y2),
+            Err(e) => panic!("{}", e),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AsteroidField {
+    asteroids: Grid<char>,
+}
+
+impl AsteroidField {
+    fn find_location(&self) -> (isize, isize, u32) {
+        let mut best_score = 0;
+        let mut best_x = 0;
+        let mut best_y = 0;
+
+        let asteroids = self.index_asteroids();
+
+        for (x, y) in asteroids.iter().cloned() {
+            let score = count_visible((x, y), &asteroids) as u32;
+
+            if score > best_score {
+                best_score = score;
+                best_x = x;
+                best_y = y;
+            }
+        }
+
+        (best_x, best_y, best_score)
+    }
+
+    fn destroy_asteroids(&self, x: isize, y: isize, bet_number: usize) -> Result<(isize, isize), String> {
+        let asteroids = self.index_asteroids();
+        let order = rotation_order((x, y), &asteroids);
+
+        match bet_number.checked_sub(1).and_then(|i| order.get(i)) {
+            Some(position) => Ok(*position),
+            None => Err(format!("Asteroid {} is never destroyed, there are only {} others.", bet_number, order.len())),
+        }
+    }
+
+    fn index_asteroids(&self) -> Vec<(isize, isize)> {
+        let mut result: Vec<(isize, isize)> = self.asteroids.positions(|c| c == '#').collect();
+        // Column by column, since ties for the best location go to the first one found.
+        result.sort_unstable();
+
+        result
+    }
+
+    fn parse(str: &str) -> Result<AsteroidField, ParseError> {
+        let grid = Grid::from_lines(str, '.', |c| match c {
+            '#' | '.' => Some(c),
+            _ => None,
+        })?;
+        Ok(AsteroidField{
+            asteroids: grid,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT1: &str = "......#.#.\n#..#.#....\n..#######.\n.#.#.###..\n.#..#.....\n..#....#.#\n#..#....#.\n.##.#..###\n##...#..#.\n.#....####\n";
+    const TEST_INPUT2: &str = ".#..##.###...#######\n##.############..##.\n.#.######.########.#\n.###.#######.####.#.\n#####.##.#.##.###.##\n..#####..#.#########\n####################\n#.####....###.#.#.##\n##.#################\n#####.##.###..####..\n..######..##.#######\n####.##.####...##..#\n.#####..#.######.###\n##...#.##########...\n#.##########.#######\n.####.#.###.###.#.##\n....##.##.###..#####\n.#.#.###########.###\n#.#.#.#####.####.###\n###.##.####.##.#..##\n";
+
+    #[test]
+    fn test_part1() {
+        let field1 = AsteroidField::parse(TEST_INPUT1).unwrap();
+        let field2 = AsteroidField::parse(TEST_INPUT2).unwrap();
+
+        assert_eq!(field1.find_location(), (5, 8, 33));
+        assert_eq!(field2.find_location(), (11,13, 210));
+    }
+
+    #[test]
+    fn test_part2() {
+        let field2 = AsteroidField::parse(TEST_INPUT2).unwrap();
+
+        assert_eq!(field2.destroy_asteroids(11, 13, 1), Ok((11, 12)));
+        assert_eq!(field2.destroy_asteroids(11, 13, 50), Ok((16, 9)));
+        assert_eq!(field2.destroy_asteroids(11, 13, 200), Ok((8, 2)));
+        assert_eq!(field2.destroy_asteroids(11, 13, 299), Ok((11, 1)));
+        assert!(field2.destroy_asteroids(11, 13, 300).is_err());
+        assert!(field2.destroy_asteroids(11, 13, 0).is_err());
+    }
+}
\ No newline at end of file
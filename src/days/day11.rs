@@ -1,30 +1,36 @@
-use common::aoc::{load_input, run_many, print_result, print_time, print_result_multiline};
-use common::intcode::{VM, StepResult};
-use std::collections::{HashSet, HashMap};
-use common::grid::Grid;
-
-fn main() {
-    let input = load_input("day11");
+use crate::aoc::Solution;
+use crate::parse::ParseError;
+use crate::intcode::{VM, StepResult};
+use std::collections::HashMap;
+use crate::grid::{Direction, Grid};
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = VM;
+    type Part1 = usize;
+    type Part2 = String;
+
+    const DAY: u32 = 11;
+    const PARSE_ITERATIONS: usize = 10000;
+    const PART1_ITERATIONS: usize = 10;
+    const PART2_ITERATIONS: usize = 10;
+
+    fn parse(input: &str) -> Result<VM, ParseError> {
+        VM::parse(input)
+    }
 
-    let (vm, dur_parse) = run_many(10000, || VM::parse(input.trim_end_matches('\n')));
-    let ((_, res_part1), dur_part1) = run_many(10, || part1(vm.clone(), 0));
-    let (res_part2, dur_part2) = run_many(10, || part2(vm.clone()));
+    fn part1(vm: &VM) -> usize {
+        let (_, count) = part1(vm.clone(), 0);
 
-    print_result("P1", res_part1);
-    print_result_multiline("P2", res_part2);
+        count
+    }
 
-    print_time("Parse", dur_parse);
-    print_time("P1", dur_part1);
-    print_time("P2", dur_part2);
+    fn part2(vm: &VM) -> String {
+        part2(vm.clone())
+    }
 }
 
-const DIRECTIONS: [(isize, isize); 4] = [
-    (0, -1),
-    (1, 0),
-    (0, 1),
-    (-1, 0),
-];
-
 const DEFAULT_PAINT: i64 = 0;
 
 fn part1(mut vm: VM, starting_color: i64) -> (HashMap<(isize, isize), i64>, usize) {
@@ -32,7 +38,7 @@ fn part1(mut vm: VM, starting_color: i64) -> (HashMap<(isize, isize), i64>, usiz
 
     let mut x = 0;
     let mut y = 0;
-    let mut dir_index = 0;
+    let mut direction = Direction::Up;
 
     vm.push_input(starting_color);
 
@@ -47,7 +53,7 @@ fn part1(mut vm: VM, starting_color: i64) -> (HashMap<(isize, isize), i64>, usiz
         let color = output[0];
         let dir_change = output[1];
 
-        dir_index = (dir_index + if dir_change == 1 { 1 } else { 3 }) % 4;
+        direction = if dir_change == 1 { direction.turn_right() } else { direction.turn_left() };
 
         match paint_map.get_mut(&(x, y)) {
             Some(v) => *v = color,
@@ -56,7 +62,7 @@ fn part1(mut vm: VM, starting_color: i64) -> (HashMap<(isize, isize), i64>, usiz
             }
         }
 
-        let (dx, dy) = DIRECTIONS[dir_index];
+        let (dx, dy) = direction.offset();
         x += dx;
         y += dy;
 
@@ -68,8 +74,8 @@ fn part1(mut vm: VM, starting_color: i64) -> (HashMap<(isize, isize), i64>, usiz
     (paint_map, count)
 }
 
-fn part2(mut vm: VM) -> String {
-    let (mut paint_map, _) = part1(vm, 1);
+fn part2(vm: VM) -> String {
+    let (paint_map, _) = part1(vm, 1);
 
     // Find boundaries
     let mut tlx = 0;
@@ -93,7 +99,7 @@ fn part2(mut vm: VM) -> String {
     let width = (brx - tlx) as usize;
     let height = (bry - tly) as usize;
 
-    let mut grid = Grid::new(width+1, height+2, -tlx, -tly, '#');
+    let mut grid = Grid::new(width+1, height+1, -tlx, -tly, '#');
 
     for ((x, y), color) in paint_map.iter() {
         if *color == 0 {
@@ -101,15 +107,5 @@ fn part2(mut vm: VM) -> String {
         }
     }
 
-    let mut result = String::with_capacity((width * height) + height);
-
-    for y in 0..=(height as isize) {
-        for x in 0..=(width as isize) {
-            result.push(grid.get(x + tlx, y + tly));
-        }
-
-        result.push('\n');
-    }
-
-    result
+    grid.to_string()
 }
\ No newline at end of file
@@ -1,23 +1,34 @@
-use common::aoc::{load_input, run_many, print_time, print_result};
+use crate::aoc::Solution;
+use crate::parse::{ParseError, Scanner};
 use num::abs;
 use std::ops::{Add, Neg, AddAssign, SubAssign};
 use std::collections::{BTreeMap};
 use std::hash::Hash;
 use num::Integer;
 
-fn main() {
-    let input = load_input("day12");
+pub struct Day12;
 
-    let (simulation, dur_parse) = run_many(10000, || Simulation::parse(&input));
-    let (res_part1, dur_part1) = run_many(10000, || part1(simulation.clone(), 1000));
-    let (res_part2, dur_part2) = run_many(10, || part2(simulation.clone()));
+impl Solution for Day12 {
+    type Input = Simulation;
+    type Part1 = i64;
+    type Part2 = usize;
 
-    print_result("P1", res_part1);
-    print_result("P2", res_part2);
+    const DAY: u32 = 12;
+    const PARSE_ITERATIONS: usize = 10000;
+    const PART1_ITERATIONS: usize = 10000;
+    const PART2_ITERATIONS: usize = 10;
 
-    print_time("Parse", dur_parse);
-    print_time("P1", dur_part1);
-    print_time("P2", dur_part2);
+    fn parse(input: &str) -> Result<Simulation, ParseError> {
+        Simulation::parse(input)
+    }
+
+    fn part1(simulation: &Simulation) -> i64 {
+        part1(simulation.clone(), 1000)
+    }
+
+    fn part2(simulation: &Simulation) -> usize {
+        part2(simulation.clone())
+    }
 }
 
 fn part1(mut simulation: Simulation, count: usize) -> i64 {
@@ -38,8 +49,8 @@ fn part2(mut simulation: Simulation) -> usize {
     let mut remaining = 3;
     let mut step_index = 0;
 
-    let mut velocities: Vec<Vec<i64>> = vec![Vec::with_capacity(simulation.moons.len()); 3];
-    let mut positions: Vec<Vec<i64>> = vec![Vec::with_capacity(simulation.moons.len()); 3];
+    let mut velocities: Vec<Vec<i64>> = (0..3).map(|_| Vec::with_capacity(simulation.moons.len())).collect();
+    let mut positions: Vec<Vec<i64>> = (0..3).map(|_| Vec::with_capacity(simulation.moons.len())).collect();
     let mut states = [(0, 0); 4];
 
     while remaining > 0 {
@@ -73,11 +84,11 @@ fn part2(mut simulation: Simulation) -> usize {
                 states[j] = (positions[i][j], velocities[i][j]);
             }
 
-            if sets[i].get(&states).is_some() {
-                cycle_lengths[i] = step_index - sets[i][&states];
+            if let Some(prev_index) = sets[i].get(&states) {
+                cycle_lengths[i] = step_index - prev_index;
                 remaining -= 1;
             } else {
-                sets[i].insert(states.clone(), step_index);
+                sets[i].insert(states, step_index);
             }
         }
 
@@ -86,15 +97,15 @@ fn part2(mut simulation: Simulation) -> usize {
     }
 
     let mut current: usize = cycle_lengths[0];
-    for i in 1..3 {
-        current = current.lcm(&cycle_lengths[i]);
+    for length in cycle_lengths.iter().skip(1) {
+        current = current.lcm(length);
     }
 
     current
 }
 
 #[derive(Clone)]
-struct Simulation {
+pub struct Simulation {
     moons: Vec<Moon>,
 }
 
@@ -126,10 +137,13 @@ impl Simulation {
         total
     }
 
-    fn parse(str: &str) -> Simulation {
-        Simulation {
-            moons: str.lines().filter(|l| l.len() > 1).map(|l| Moon::parse(l)).collect(),
-        }
+    fn parse(str: &str) -> Result<Simulation, ParseError> {
+        let moons = str.lines().enumerate()
+            .filter(|(_, l)| l.len() > 1)
+            .map(|(i, l)| Moon::parse(l).map_err(|e| e.on_line(i + 1)))
+            .collect::<Result<Vec<Moon>, ParseError>>()?;
+
+        Ok(Simulation { moons })
     }
 }
 
@@ -178,35 +192,31 @@ impl Moon {
         velocity
     }
 
-    fn parse(line: &str) -> Moon {
+    fn parse(line: &str) -> Result<Moon, ParseError> {
         let mut arr = [0i64; 3];
-        let mut parsed = 0i64;
-        let mut sign = 1;
-        let mut idx = 0;
-
-        for ch in line.chars() {
-            match ch {
-                '0'..='9' => {
-                    parsed *= 10;
-                    parsed += ((ch as u8) - ('0' as u8)) as i64;
-                }
-                '-' => {
-                    sign = -1;
-                }
-                ',' => {
-                    arr[idx] = parsed * sign;
-                    sign = 1;
-                    parsed = 0;
-                    idx += 1;
-                },
-                _ => {}
+        let mut scanner = Scanner::new(line);
+
+        scanner.expect(b'<')?;
+        for (i, axis) in b"xyz".iter().enumerate() {
+            if i > 0 {
+                scanner.skip_spaces();
+                scanner.expect(b',')?;
             }
+            scanner.skip_spaces();
+
+            scanner.expect(*axis)?;
+            scanner.expect(b'=')?;
+            scanner.skip_spaces();
+            arr[i] = scanner.signed()?;
         }
+        scanner.skip_spaces();
+        scanner.expect(b'>')?;
+        scanner.end()?;
 
-        Moon {
-            position: Point(arr[0], arr[1], parsed * sign),
+        Ok(Moon {
+            position: Point(arr[0], arr[1], arr[2]),
             velocity: Point(0, 0, 0),
-        }
+        })
     }
 }
 
@@ -260,11 +270,12 @@ impl Neg for Point {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parse::assert_scanner_speed;
 
     #[test]
     fn test_moon_parse() {
-        let moon = Moon::parse("<x=2, y=-10, z=-7>");
-        let moon2 = Moon::parse("<x=2432, y=1110, z=-17>");
+        let moon = Moon::parse("<x=2, y=-10, z=-7>").unwrap();
+        let moon2 = Moon::parse("<x=2432, y=1110, z=-17>").unwrap();
 
         assert_eq!(moon.position, Point(2, -10, -7));
         assert_eq!(moon2.position, Point(2432, 1110, -17));
@@ -272,8 +283,8 @@ mod tests {
 
     #[test]
     fn test_moon_simulate_gravity() {
-        let moon = Moon::parse("<x=-1, y=  5, z= 1>");
-        let moon2 = Moon::parse("<x=1, y=  5, z= -1>");
+        let moon = Moon::parse("<x=-1, y=  5, z= 1>").unwrap();
+        let moon2 = Moon::parse("<x=1, y=  5, z= -1>").unwrap();
 
         let velocity = moon.simulate_gravity(&moon2);
 
@@ -283,7 +294,7 @@ mod tests {
     const TEST_DATA: &str = "<x=-8, y=-10, z=0>\n<x=5, y=5, z=10>\n<x=2, y=-7, z=3>\n<x=9, y=-8, z=-3>";
     #[test]
     fn test_part1() {
-        let mut sim = Simulation::parse(TEST_DATA);
+        let mut sim = Simulation::parse(TEST_DATA).unwrap();
 
         for i in 0..100 {
             println!("Step {}", i);
@@ -305,8 +316,49 @@ mod tests {
 
     #[test]
     fn test_part2() {
-        let amount = part2(Simulation::parse(TEST_DATA_EASY));
+        let amount = part2(Simulation::parse(TEST_DATA_EASY).unwrap());
 
         assert_eq!(amount, 2772);
     }
-}
\ No newline at end of file
+
+    #[test]
+    #[ignore]
+    fn bench_scanner() {
+        fn hand_rolled(line: &str) -> Point {
+            let mut arr = [0i64; 3];
+            let mut parsed = 0i64;
+            let mut sign = 1;
+            let mut idx = 0;
+
+            for ch in line.chars() {
+                match ch {
+                    '0'..='9' => {
+                        parsed *= 10;
+                        parsed += ((ch as u8) - b'0') as i64;
+                    }
+                    '-' => {
+                        sign = -1;
+                    }
+                    ',' => {
+                        arr[idx] = parsed * sign;
+                        sign = 1;
+                        parsed = 0;
+                        idx += 1;
+                    },
+                    _ => {}
+                }
+            }
+
+            Point(arr[0], arr[1], parsed * sign)
+        }
+
+        let lines: Vec<String> = (0..1000i64)
+            .map(|n| format!("<x={}, y={}, z={}>", n % 37 - 18, n * 7 % 101 - 50, -n))
+            .collect();
+
+        assert_scanner_speed(
+            || lines.iter().map(|l| hand_rolled(l)).collect::<Vec<_>>(),
+            || lines.iter().map(|l| Moon::parse(l).unwrap().position).collect::<Vec<_>>(),
+        );
+    }
+}
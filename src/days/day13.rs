@@ -1,20 +1,35 @@
-use common::aoc::{load_input, run_many, print_time, print_result};
-use common::intcode::VM;
+use crate::aoc::Solution;
+use crate::parse::ParseError;
+use crate::intcode::VM;
 use num::clamp;
 
-fn main() {
-    let input = load_input("day13");
+pub struct Day13;
 
-    let (game, dur_parse) = run_many(100, || Game::new(&input.trim_end_matches("\n")));
-    let ((game, res_part1), dur_part1) = run_many(10, || part1(game.clone()));
-    let (res_part2, dur_part2) = run_many(1, || part2(game.clone()));
+impl Solution for Day13 {
+    type Input = Game;
+    type Part1 = usize;
+    type Part2 = i64;
 
-    print_result("P1", res_part1);
-    print_result("P2", res_part2);
+    const DAY: u32 = 13;
+    const PARSE_ITERATIONS: usize = 100;
+    const PART1_ITERATIONS: usize = 10;
+    const PART2_ITERATIONS: usize = 1;
 
-    print_time("Parse", dur_parse);
-    print_time("P1", dur_part1);
-    print_time("P2", dur_part2);
+    fn parse(input: &str) -> Result<Game, ParseError> {
+        Game::new(input)
+    }
+
+    fn part1(game: &Game) -> usize {
+        let (_, count) = part1(game.clone());
+
+        count
+    }
+
+    fn part2(game: &Game) -> i64 {
+        let (game, _) = part1(game.clone());
+
+        part2(game)
+    }
 }
 
 fn part1(mut game: Game) -> (Game, usize) {
@@ -35,7 +50,7 @@ fn part2(mut game: Game) -> i64 {
 }
 
 #[derive(Clone)]
-struct Game {
+pub struct Game {
     vm: VM,
     block_count: usize,
     score: i64,
@@ -69,7 +84,7 @@ impl Game {
         let (px, _) = self.paddle_pos;
         let (bx, _) = self.ball_pos;
 
-        self.vm.push_input(clamp((bx - px) as i64, -1, 1));
+        self.vm.push_input(clamp(bx - px, -1, 1));
         self.vm.run();
 
         let output = self.vm.read_output();
@@ -95,13 +110,13 @@ impl Game {
         }
     }
 
-    fn new(input: &str) -> Game {
-        Game{
-            vm: VM::parse(input),
+    fn new(input: &str) -> Result<Game, ParseError> {
+        Ok(Game{
+            vm: VM::parse(input)?,
             block_count: 0,
             ball_pos: (0, 0),
             paddle_pos: (0, 0),
             score: 0,
-        }
+        })
     }
 }
\ No newline at end of file
@@ -1,22 +1,32 @@
-use common::aoc::{load_input, run_many, print_time, print_result};
+use crate::aoc::Solution;
+use crate::parse::{ParseError, Scanner};
 use std::collections::HashMap;
 
-fn main() {
-    let input = load_input("day14");
+pub struct Day14;
 
-    let (chain, dur_parse) = run_many(1000, || ReactionChain::parse(&input));
-    let (res_part1, dur_part1) = run_many(100, || chain.min_opf(1));
-    let (res_part2, dur_part2) = run_many(100, || chain.max_fpo(1_000_000_000_000));
+impl Solution for Day14 {
+    type Input = ReactionChain;
+    type Part1 = u64;
+    type Part2 = u64;
 
-    print_result("P1", res_part1);
-    print_result("P2", res_part2);
+    const DAY: u32 = 14;
+    const PART1_ITERATIONS: usize = 100;
+    const PART2_ITERATIONS: usize = 100;
 
-    print_time("Parse", dur_parse);
-    print_time("P1", dur_part1);
-    print_time("P2", dur_part2);
+    fn parse(input: &str) -> Result<ReactionChain, ParseError> {
+        ReactionChain::parse(input)
+    }
+
+    fn part1(chain: &ReactionChain) -> u64 {
+        chain.min_opf(1)
+    }
+
+    fn part2(chain: &ReactionChain) -> u64 {
+        chain.max_fpo(1_000_000_000_000)
+    }
 }
 
-struct ReactionChain {
+pub struct ReactionChain {
     list: Vec<Material>,
     map: HashMap<String, usize>,
 }
@@ -39,6 +49,7 @@ impl ReactionChain {
         index
     }
 
+    #[allow(clippy::manual_is_multiple_of)]
     fn min_opf(&self, fuel_count: u64) -> u64 {
         let mut amounts = vec![0; self.list.len()];
         let mut completed = vec![false; self.list.len()];
@@ -51,9 +62,7 @@ impl ReactionChain {
 
         remaining.swap_remove(ore_index);
 
-        while remaining.len() > 0 {
-            let mat_index = remaining.pop().unwrap();
-
+        while let Some(mat_index) = remaining.pop() {
             // Fabricate not until all of the material is present.
             let mut mise_en_place = true;
             for dep_index in self.list[mat_index].dependents.iter().cloned() {
@@ -67,7 +76,7 @@ impl ReactionChain {
                 deferred.push(mat_index);
 
                 // Before quitting, make sure that there aren't any deferred materials.
-                if remaining.len() == 0 {
+                if remaining.is_empty() {
                     remaining.extend(deferred.iter());
                     deferred.clear();
                 }
@@ -87,7 +96,7 @@ impl ReactionChain {
             completed[mat_index] = true;
 
             // Before quitting, make sure that there aren't any deferred materials.
-            if remaining.len() == 0 {
+            if remaining.is_empty() {
                 remaining.extend(deferred.iter());
                 deferred.clear();
             }
@@ -127,29 +136,26 @@ impl ReactionChain {
         }
     }
 
-    fn parse(input: &str) -> ReactionChain {
+    fn parse(input: &str) -> Result<ReactionChain, ParseError> {
         let mut chain = Self::new();
+        let mut scanner = Scanner::new(input);
 
         let ore_index = chain.ensure("ORE");
         chain.list[ore_index].amount = 1;
 
-        for line in input.lines() {
-            if line.len() == 0 {
-                continue;
-            }
+        scanner.skip_whitespace();
+        while !scanner.is_done() {
+            let dependencies = scanner.separated(b',', parse_mat_qty)?;
+            scanner.separator("=>")?;
+            let (result_name, result_amount) = parse_mat_qty(&mut scanner)?;
+            scanner.newline()?;
+            scanner.skip_whitespace();
 
-            let (left, right) = {
-                let mut tokens = line.split("=>");
-                (tokens.next().unwrap(), tokens.next().unwrap())
-            };
-
-            let (result_name, result_amount) = parse_mat_qty(right);
             let result_index = chain.ensure(result_name);
 
             chain.list[result_index].amount = result_amount;
 
-            for token in left.split(',') {
-                let (dependency_name, dependency_amount) = parse_mat_qty(token);
+            for (dependency_name, dependency_amount) in dependencies {
                 let dependency_index = chain.ensure(dependency_name);
 
                 chain.list[result_index].dependencies.push(Dependency{
@@ -161,7 +167,7 @@ impl ReactionChain {
             }
         }
 
-        chain
+        Ok(chain)
     }
 }
 
@@ -176,43 +182,35 @@ struct Dependency {
     amount: u64,
 }
 
-fn parse_mat_qty(s: &str) -> (&str, u64) {
-    let mut amount = 0;
-    let mut start_index = 0;
-    let mut end_index = 0;
-
-    for (i, ch) in s.chars().enumerate() {
-        match ch {
-            'A'..='Z' => {
-                if start_index == 0 {
-                    start_index = i;
-                    end_index = i;
-                } else {
-                    end_index = i;
-                }
-            }
-            '0'..='9' => {
-                amount *= 10;
-                amount += (ch as u8 - '0' as u8) as u64;
-            }
-            _ => {}
-        }
+fn parse_mat_qty<'a>(scanner: &mut Scanner<'a>) -> Result<(&'a str, u64), ParseError> {
+    scanner.skip_spaces();
+    let amount = scanner.unsigned()?;
+    scanner.skip_spaces();
+
+    let name = scanner.take_while(|b| b.is_ascii_uppercase());
+    if name.is_empty() {
+        return Err(scanner.unexpected("a chemical"));
     }
+    scanner.skip_spaces();
 
-    (&s[start_index..=end_index], amount)
+    Ok((name, amount))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parse::assert_scanner_speed;
 
     #[test]
     fn test_parse_mat_qty() {
-        assert_eq!(parse_mat_qty("9 ORE"), ("ORE", 9));
-        assert_eq!(parse_mat_qty(" 1 GPVTF"), ("GPVTF", 1));
-        assert_eq!(parse_mat_qty(" 7 PSHF "), ("PSHF", 7));
-        assert_eq!(parse_mat_qty("179 ORE "), ("ORE", 179));
-        assert_eq!(parse_mat_qty(" 2 A"), ("A", 2));
+        let parse = |s| parse_mat_qty(&mut Scanner::new(s));
+
+        assert_eq!(parse("9 ORE"), Ok(("ORE", 9)));
+        assert_eq!(parse(" 1 GPVTF"), Ok(("GPVTF", 1)));
+        assert_eq!(parse(" 7 PSHF "), Ok(("PSHF", 7)));
+        assert_eq!(parse("179 ORE "), Ok(("ORE", 179)));
+        assert_eq!(parse(" 2 A"), Ok(("A", 2)));
+        assert_eq!(parse("2 a").unwrap_err().to_string(), "line 1, column 3: expected a chemical, found 'a'");
     }
 
     const TEST_INPUT1: &str = "9 ORE => 2 A\n8 ORE => 3 B\n7 ORE => 5 C\n3 A, 4 B => 1 AB\n5 B, 7 C => 1 BC\n4 C, 1 A => 1 CA\n2 AB, 3 BC, 4 CA => 1 FUEL\n";
@@ -222,10 +220,10 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let test1 = ReactionChain::parse(TEST_INPUT1);
-        let test2 = ReactionChain::parse(TEST_INPUT2);
-        let test3 = ReactionChain::parse(TEST_INPUT3);
-        let test4 = ReactionChain::parse(TEST_INPUT4);
+        let test1 = ReactionChain::parse(TEST_INPUT1).unwrap();
+        let test2 = ReactionChain::parse(TEST_INPUT2).unwrap();
+        let test3 = ReactionChain::parse(TEST_INPUT3).unwrap();
+        let test4 = ReactionChain::parse(TEST_INPUT4).unwrap();
 
         assert_eq!(test1.min_opf(1), 165);
         assert_eq!(test2.min_opf(1), 13312);
@@ -237,31 +235,63 @@ mod tests {
 
     #[test]
     fn test_part2() {
-        let test2 = ReactionChain::parse(TEST_INPUT2);
-        let test3 = ReactionChain::parse(TEST_INPUT3);
-        let test4 = ReactionChain::parse(TEST_INPUT4);
+        let test2 = ReactionChain::parse(TEST_INPUT2).unwrap();
+        let test3 = ReactionChain::parse(TEST_INPUT3).unwrap();
+        let test4 = ReactionChain::parse(TEST_INPUT4).unwrap();
 
         assert_eq!(test2.max_fpo(PART2_GOAL), 82892753);
         assert_eq!(test3.max_fpo(PART2_GOAL), 5586022);
         assert_eq!(test4.max_fpo(PART2_GOAL), 460664);
     }
-}
-
-
-
-
-
-
-
-
-
-
-
-
-
-
-
 
+    #[test]
+    #[ignore]
+    fn bench_scanner() {
+        fn hand_rolled(s: &str) -> (&str, u64) {
+            let mut amount = 0;
+            let mut start_index = 0;
+            let mut end_index = 0;
+
+            for (i, ch) in s.chars().enumerate() {
+                match ch {
+                    'A'..='Z' => {
+                        if start_index == 0 {
+                            start_index = i;
+                        }
+                        end_index = i;
+                    }
+                    '0'..='9' => {
+                        amount *= 10;
+                        amount += (ch as u8 - b'0') as u64;
+                    }
+                    _ => {}
+                }
+            }
 
+            (&s[start_index..=end_index], amount)
+        }
 
+        let input: String = (0..1000u64)
+            .map(|n| format!("{} AB, {} CDE, {} FGHI => {} JKL\n", n % 9 + 1, n % 13 + 2, n % 17 + 3, n % 5 + 1))
+            .collect();
+
+        assert_scanner_speed(
+            || input.lines()
+                .flat_map(|line| line.split(" => ").flat_map(|side| side.split(", ")))
+                .map(hand_rolled)
+                .collect::<Vec<_>>(),
+            || {
+                let mut scanner = Scanner::new(&input);
+                let mut result = Vec::with_capacity(4000);
+                while !scanner.is_done() {
+                    result.extend(scanner.separated(b',', parse_mat_qty).unwrap());
+                    scanner.separator("=>").unwrap();
+                    result.push(parse_mat_qty(&mut scanner).unwrap());
+                    scanner.newline().unwrap();
+                }
 
+                result
+            },
+        );
+    }
+}
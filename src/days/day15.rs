@@ -0,0 +1,163 @@
+use crate::aoc::Solution;
+use crate::parse::ParseError;
+use crate::intcode::VM;
+use crate::grid::{bfs, flood_fill, orthogonal, BigGrid, Direction, Point};
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input = VM;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    const DAY: u32 = 15;
+    const PART1_ITERATIONS: usize = 100;
+    const PART2_ITERATIONS: usize = 100;
+
+    fn parse(input: &str) -> Result<VM, ParseError> {
+        VM::parse(input)
+    }
+
+    fn part1(vm: &VM) -> usize {
+        let (map, oxygen) = explore(droid(vm.clone()));
+
+        distance_to(&map, oxygen)
+    }
+
+    fn part2(vm: &VM) -> usize {
+        let (map, oxygen) = explore(droid(vm.clone()));
+
+        fill_time(&map, oxygen)
+    }
+}
+
+const UNKNOWN: char = ' ';
+const WALL: char = '#';
+const OPEN: char = '.';
+const OXYGEN: char = 'O';
+
+/// The droid's replies to a move: it hit a wall, moved, or moved onto the oxygen system.
+const HIT_WALL: i64 = 0;
+const MOVED: i64 = 1;
+const FOUND_OXYGEN: i64 = 2;
+
+fn droid(mut vm: VM) -> impl FnMut(Direction) -> i64 {
+    vm.reset();
+
+    move |direction| {
+        let command = match direction {
+            Direction::Up => 1,
+            Direction::Down => 2,
+            Direction::Left => 3,
+            Direction::Right => 4,
+        };
+
+        vm.push_input(command);
+        vm.run();
+
+        match vm.read_output().last() {
+            Some(status @ HIT_WALL..=FOUND_OXYGEN) => *status,
+            status => panic!("invalid response: {:?}", status),
+        }
+    }
+}
+
+/// Walks the droid into every cell it can reach, depth first, and backs it out of dead ends.
+/// Returns the map, with the droid starting at `(0, 0)`, and where the oxygen system is.
+fn explore(mut step: impl FnMut(Direction) -> i64) -> (BigGrid<char>, Point) {
+    let mut map = BigGrid::new(UNKNOWN);
+    let mut position = (0, 0);
+    let mut oxygen = None;
+    // How the droid got to each cell on the way here, so it can go back the same way.
+    let mut path: Vec<Direction> = Vec::with_capacity(128);
+
+    map.set(0, 0, OPEN);
+
+    loop {
+        let unexplored = Direction::ALL.iter().cloned()
+            .find(|direction| {
+                let (x, y) = direction.step(position);
+                map.get(x, y) == UNKNOWN
+            });
+
+        match unexplored {
+            Some(direction) => {
+                let (x, y) = direction.step(position);
+
+                match step(direction) {
+                    HIT_WALL => {
+                        map.set(x, y, WALL);
+                        continue;
+                    }
+                    MOVED => map.set(x, y, OPEN),
+                    _ => {
+                        map.set(x, y, OXYGEN);
+                        oxygen = Some((x, y));
+                    }
+                }
+
+                path.push(direction);
+                position = (x, y);
+            }
+            None => match path.pop() {
+                Some(direction) => {
+                    step(direction.reverse());
+                    position = direction.reverse().step(position);
+                }
+                None => break,
+            },
+        }
+    }
+
+    (map, oxygen.expect("the droid never found the oxygen system"))
+}
+
+fn is_open(c: char) -> bool {
+    c == OPEN || c == OXYGEN
+}
+
+fn distance_to(map: &BigGrid<char>, oxygen: Point) -> usize {
+    bfs(map, (0, 0), is_open, orthogonal).get(oxygen).unwrap() as usize
+}
+
+/// How long it takes the oxygen to spread from the oxygen system to every open cell.
+fn fill_time(map: &BigGrid<char>, oxygen: Point) -> usize {
+    flood_fill(map, Some(oxygen), is_open, orthogonal).max()
+        .map(|(_, distance)| distance as usize)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+
+    /// A droid on a known map, starting at `start`.
+    fn fake_droid(map: &str, start: Point) -> impl FnMut(Direction) -> i64 {
+        let grid = Grid::from_lines(map, UNKNOWN, Some).unwrap();
+        let mut position = start;
+
+        move |direction| {
+            let (x, y) = direction.step(position);
+
+            match grid.get_oob(x, y) {
+                OPEN => { position = (x, y); MOVED }
+                OXYGEN => { position = (x, y); FOUND_OXYGEN }
+                _ => HIT_WALL,
+            }
+        }
+    }
+
+    const MAP: &str = " ##   \n#..## \n#.#..#\n#.O.# \n ###  \n";
+
+    #[test]
+    fn test_explore() {
+        let (map, oxygen) = explore(fake_droid(MAP, (4, 2)));
+
+        assert_eq!(oxygen, (-2, 1));
+        assert_eq!(map.get(-1, 0), OPEN);
+        assert_eq!(map.get(1, 0), WALL);
+        assert_eq!(distance_to(&map, oxygen), 3);
+        assert_eq!(fill_time(&map, oxygen), 4);
+    }
+}
@@ -1,47 +1,61 @@
-use common::aoc::{load_input, run_many, print_time, print_result, run_once};
+use crate::aoc::Solution;
+use crate::parse::{ParseError, Scanner};
 use num::{abs, range_step};
 
-fn main() {
-    let input = load_input("day16");
+pub struct Day16;
 
-    let (numbers, dur_parse) = run_many(1000, || parse_input(&input));
-    let (res_part1, dur_part1) = run_once(|| part1(&numbers));
-    let (res_part2, dur_part2) = run_once(|| part2(&numbers));
+impl Solution for Day16 {
+    type Input = Vec<i64>;
+    type Part1 = i64;
+    type Part2 = i64;
 
-    print_result("P1", res_part1);
-    print_result("P2", res_part2);
+    const DAY: u32 = 16;
+    const PART1_ITERATIONS: usize = 1;
+    const PART2_ITERATIONS: usize = 1;
 
-    print_time("Parse", dur_parse);
-    print_time("P1", dur_part1);
-    print_time("P2", dur_part2);
+    fn parse(input: &str) -> Result<Vec<i64>, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(numbers: &Vec<i64>) -> i64 {
+        part1(numbers)
+    }
+
+    fn part2(numbers: &Vec<i64>) -> i64 {
+        part2(numbers)
+    }
 }
 
 const PATTERN: [i64; 4] = [0, 1, 0, -1];
 
-fn parse_input(input: &str) -> Vec<i64> {
-    input.chars()
-        .filter(|ch| *ch >= '0' && *ch <= '9')
-        .map(|ch| (ch as u8 - '0' as u8) as i64)
-        .collect()
+fn parse_input(input: &str) -> Result<Vec<i64>, ParseError> {
+    let mut numbers = Vec::with_capacity(input.len());
+    let mut scanner = Scanner::new(input);
+
+    while let Some(b'0'..=b'9') = scanner.peek() {
+        numbers.push(scanner.digit()? as i64);
+    }
+    scanner.end()?;
+
+    Ok(numbers)
 }
 
 fn part1(numbers: &[i64]) -> i64 {
     let mut prev = numbers.to_vec();
     let mut curr = vec![0; numbers.len()];
-    let len = curr.len();
 
     for _ in 0..100 {
-        for i in 0..len {
+        for (i, out) in curr.iter_mut().enumerate() {
             let pat_size = i + 1;
             let mut sum = 0;
 
-            for j in i..len {
+            for (j, value) in prev.iter().enumerate().skip(i) {
                 let pat_index = ((j + 1) / pat_size) % PATTERN.len();
 
-                sum += PATTERN[pat_index] * prev[j];
+                sum += PATTERN[pat_index] * value;
             }
 
-            curr[i] = abs(sum) % 10;
+            *out = abs(sum) % 10;
         }
 
         prev.copy_from_slice(&curr);
@@ -96,15 +110,15 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&parse_input("80871224585914546619083218645595")), 24176176);
-        assert_eq!(part1(&parse_input("19617804207202209144916044189917")), 73745418);
-        assert_eq!(part1(&parse_input("69317163492948606335995924319873")), 52432133);
+        assert_eq!(part1(&parse_input("80871224585914546619083218645595").unwrap()), 24176176);
+        assert_eq!(part1(&parse_input("19617804207202209144916044189917").unwrap()), 73745418);
+        assert_eq!(part1(&parse_input("69317163492948606335995924319873").unwrap()), 52432133);
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(&parse_input("03036732577212944063491565474664")), 84462026);
-        assert_eq!(part2(&parse_input("02935109699940807407585447034323")), 78725270);
-        assert_eq!(part2(&parse_input("03081770884921959731165446850517")), 53553731);
+        assert_eq!(part2(&parse_input("03036732577212944063491565474664").unwrap()), 84462026);
+        assert_eq!(part2(&parse_input("02935109699940807407585447034323").unwrap()), 78725270);
+        assert_eq!(part2(&parse_input("03081770884921959731165446850517").unwrap()), 53553731);
     }
 }
\ No newline at end of file
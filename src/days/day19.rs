@@ -1,20 +1,29 @@
-use common::aoc::{load_input, run_many, run_many_mut, print_result, print_time};
-use common::intcode::VM;
-use common::grid::{Grid, BigGrid};
+use crate::aoc::Solution;
+use crate::parse::ParseError;
+use crate::intcode::VM;
 
-fn main() {
-    let input = load_input("day19");
+pub struct Day19;
 
-    let (mut vm, dur_parse) = run_many(1000, || VM::parse(&input.trim_end_matches("\n")));
-    let (res_part1, dur_part1) = run_many_mut(100, || part1(&mut vm));
-    let (res_part2, dur_part2) = run_many_mut(1, || part2(&mut vm));
+impl Solution for Day19 {
+    type Input = VM;
+    type Part1 = usize;
+    type Part2 = i64;
 
-    print_result("P1", res_part1);
-    print_result("P2", res_part2);
+    const DAY: u32 = 19;
+    const PART1_ITERATIONS: usize = 100;
+    const PART2_ITERATIONS: usize = 1;
 
-    print_time("Parse", dur_parse);
-    print_time("P1", dur_part1);
-    print_time("P2", dur_part2);
+    fn parse(input: &str) -> Result<VM, ParseError> {
+        VM::parse(input)
+    }
+
+    fn part1(vm: &VM) -> usize {
+        part1(&mut vm.clone())
+    }
+
+    fn part2(vm: &VM) -> i64 {
+        part2(&mut vm.clone())
+    }
 }
 
 
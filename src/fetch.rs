@@ -0,0 +1,218 @@
+use std::env;
+use std::fmt::{self, Display};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+use crate::aoc::{day_name, input_dir};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com/2019";
+pub const SESSION_VAR: &str = "AOC_SESSION";
+const USER_AGENT: &str = concat!("aoc2019-fetch/", env!("CARGO_PKG_VERSION"));
+
+#[derive(Debug)]
+pub enum Fetched {
+    Cached,
+    Downloaded,
+    Locked,
+}
+
+#[derive(Debug)]
+pub enum FetchError {
+    Http { day: u32, status: u16, message: String },
+    Transport { day: u32, message: String },
+    Io { path: PathBuf, error: io::Error },
+}
+
+impl Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::Http { day, status, message } => {
+                write!(f, "Day {}: HTTP {}: {}", day, status, message)?;
+
+                match status {
+                    400 | 401 | 403 => write!(f, " (is the session cookie still valid?)"),
+                    404 => write!(f, " (is the puzzle unlocked yet?)"),
+                    _ => Ok(()),
+                }
+            }
+            FetchError::Transport { day, message } => write!(f, "Day {}: {}", day, message),
+            FetchError::Io { path, error } => write!(f, "Could not write {}: {}", path.display(), error),
+        }
+    }
+}
+
+pub struct Fetcher {
+    pub base_url: String,
+    pub session: String,
+    pub dir: PathBuf,
+}
+
+impl Fetcher {
+    pub fn new(base_url: &str, session: &str) -> Fetcher {
+        Fetcher {
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            session: session.to_owned(),
+            dir: input_dir(),
+        }
+    }
+
+    pub fn input_url(&self, day: u32) -> String {
+        format!("{}/day/{}/input", self.base_url, day)
+    }
+
+    /// Downloads the input for `day` unless it is already in the input directory, or the
+    /// puzzle isn't unlocked at `now`. Empty files, like the ones `aoc new` makes, don't count.
+    pub fn fetch(&self, day: u32, now: DateTime<Utc>) -> Result<Fetched, FetchError> {
+        let path = self.dir.join(format!("{}.txt", day_name(day)));
+        if fs::metadata(&path).map(|m| m.len() > 0).unwrap_or(false) {
+            return Ok(Fetched::Cached);
+        }
+        if !is_unlocked(day, now) {
+            return Ok(Fetched::Locked);
+        }
+
+        let input = self.download(day)?;
+
+        fs::create_dir_all(&self.dir)
+            .and_then(|_| fs::write(&path, normalize_input(&input)))
+            .map_err(|error| FetchError::Io { path, error })?;
+
+        Ok(Fetched::Downloaded)
+    }
+
+    fn download(&self, day: u32) -> Result<String, FetchError> {
+        let response = ureq::get(&self.input_url(day))
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", USER_AGENT)
+            .call();
+
+        match response {
+            Ok(response) => response.into_string()
+                .map_err(|e| FetchError::Transport { day, message: e.to_string() }),
+            Err(ureq::Error::Status(status, response)) => {
+                let status_text = response.status_text().to_owned();
+                let body = response.into_string().unwrap_or_default();
+                let message = match body.lines().map(|l| l.trim()).find(|l| !l.is_empty()) {
+                    Some(line) => format!("{}: {}", status_text, line),
+                    None => status_text,
+                };
+
+                Err(FetchError::Http { day, status, message })
+            }
+            Err(e) => Err(FetchError::Transport { day, message: e.to_string() }),
+        }
+    }
+}
+
+/// Puzzles unlock at midnight EST.
+pub fn unlock_time(day: u32) -> DateTime<FixedOffset> {
+    FixedOffset::west_opt(5 * 3600).unwrap()
+        .with_ymd_and_hms(2019, 12, day, 0, 0, 0).unwrap()
+}
+
+pub fn is_unlocked(day: u32, now: DateTime<Utc>) -> bool {
+    now >= unlock_time(day)
+}
+
+pub fn normalize_input(input: &str) -> String {
+    let mut input = input.trim_end_matches(['\n', '\r']).to_owned();
+    input.push('\n');
+
+    input
+}
+
+/// Reads the session cookie from `AOC_SESSION`, or else from an `AOC_SESSION=...` line in the
+/// project's `cookie.env`.
+pub fn load_session() -> Option<String> {
+    if let Ok(session) = env::var(SESSION_VAR) {
+        return Some(session);
+    }
+
+    let data = fs::read_to_string(Path::new(env!("CARGO_MANIFEST_DIR")).join("cookie.env")).ok()?;
+
+    data.lines()
+        .map(|l| l.trim().trim_start_matches("export "))
+        .find_map(|l| l.strip_prefix("AOC_SESSION="))
+        .map(|s| s.trim_matches(|c| c == '"' || c == '\'').to_owned())
+        .filter(|s| !s.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::process;
+    use std::thread;
+
+    fn serve(requests: usize) -> (String, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let mut seen = Vec::new();
+
+            for stream in listener.incoming().take(requests) {
+                let mut stream = stream.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let n = stream.read(&mut buf).unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                }
+                let request = String::from_utf8(request).unwrap();
+
+                let (status, body) = if request.starts_with("GET /day/1/input ") {
+                    ("200 OK", "1\n2\n\n\n")
+                } else {
+                    ("400 Bad Request", "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n")
+                };
+                write!(stream, "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", status, body.len(), body).unwrap();
+
+                seen.push(request);
+            }
+
+            seen
+        });
+
+        (base_url, handle)
+    }
+
+    #[test]
+    fn test_fetch() {
+        let (base_url, server) = serve(2);
+        let mut fetcher = Fetcher::new(&base_url, "abc123");
+        fetcher.dir = env::temp_dir().join(format!("aoc-fetch-test-{}", process::id()));
+        let now = Utc.with_ymd_and_hms(2019, 12, 2, 5, 0, 0).unwrap();
+
+        assert!(matches!(fetcher.fetch(1, now), Ok(Fetched::Downloaded)));
+        assert!(matches!(fetcher.fetch(1, now), Ok(Fetched::Cached)));
+        assert!(matches!(fetcher.fetch(3, now), Ok(Fetched::Locked)));
+
+        let err = fetcher.fetch(2, now).unwrap_err();
+        assert!(matches!(err, FetchError::Http { day: 2, status: 400, .. }));
+        assert!(err.to_string().contains("Please log in"));
+
+        let input = fs::read_to_string(fetcher.dir.join("day01.txt")).unwrap();
+        fs::remove_dir_all(&fetcher.dir).unwrap();
+
+        assert_eq!(input, "1\n2\n");
+
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].contains("session=abc123"));
+    }
+
+    #[test]
+    fn test_unlock_time() {
+        assert!(!is_unlocked(5, Utc.with_ymd_and_hms(2019, 12, 5, 4, 59, 59).unwrap()));
+        assert!(is_unlocked(5, Utc.with_ymd_and_hms(2019, 12, 5, 5, 0, 0).unwrap()));
+    }
+
+    #[test]
+    fn test_normalize_input() {
+        assert_eq!(normalize_input("1\n2\r\n\n"), "1\n2\n");
+        assert_eq!(normalize_input("1"), "1\n");
+    }
+}
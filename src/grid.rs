@@ -1,87 +1,113 @@
-use std::cmp::Ordering;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
 use std::f64::consts::PI;
+use std::fmt::{self, Display, Write};
 use num::abs;
 use crate::num::Integer;
+use crate::parse::ParseError;
 
+const CHUNK_SIZE: isize = 32;
+
+/// A grid that grows in every direction as it is written to, 32x32 chunks at a time.
 #[derive(Clone)]
 pub struct BigGrid<T> {
     chunks: Vec<[T; 1024]>,
-    map: Grid<usize>,
+    /// The top left corner of each chunk.
+    origins: Vec<Point>,
+    /// Which chunk covers each block of 32x32 cells.
+    map: HashMap<Point, usize>,
     default_value: T,
-    tl_x: isize,
-    tl_y: isize,
-    br_x: isize,
-    br_y: isize,
+    /// The top left and bottom right corners of what has been written to, both inclusive.
+    bounds: Option<(Point, Point)>,
 }
 
 impl<T> BigGrid<T> where T: Clone + Copy + std::fmt::Debug {
-    fn chunk_index(&self, x: isize, y: isize) -> (usize, isize, isize, usize) {
-        let chunk_x = if x < 0 { (x / 32) - 1 } else { x / 32 };
-        let chunk_y = if y < 0 { (y / 32) - 1 } else { y / 32 };
-
-        let chunk_index = self.map.get(chunk_x, chunk_y);
-        let relative_x = x - (chunk_x * 32);
-        let relative_y = y - (chunk_y * 32);
-        let relative_index = (relative_y * 32 + relative_x) as usize;
+    fn chunk_index(x: isize, y: isize) -> (Point, usize) {
+        let chunk = (x.div_euclid(CHUNK_SIZE), y.div_euclid(CHUNK_SIZE));
+        let relative_index = (y.rem_euclid(CHUNK_SIZE) * CHUNK_SIZE + x.rem_euclid(CHUNK_SIZE)) as usize;
 
-        (chunk_index, chunk_x, chunk_y, relative_index)
+        (chunk, relative_index)
     }
 
     pub fn get(&self, x: isize, y: isize) -> T {
-        let (chunk_index, _, _, relative_index) = self.chunk_index(x, y);
-        if chunk_index == 0 {
-            return self.default_value;
-        }
-
-        let chunk = &self.chunks[chunk_index - 1];
+        let (chunk, relative_index) = Self::chunk_index(x, y);
 
-        chunk[relative_index]
+        match self.map.get(&chunk) {
+            Some(index) => self.chunks[*index][relative_index],
+            None => self.default_value,
+        }
     }
 
     pub fn get_mut(&mut self, x: isize, y: isize) -> &mut T {
-        let (chunk_index, chunk_x, chunk_y, relative_index) = self.chunk_index(x, y);
+        let (chunk, relative_index) = Self::chunk_index(x, y);
 
-        let chunk = if chunk_index > 0 {
-            &mut self.chunks[chunk_index - 1]
-        } else {
-            self.map.set(chunk_x, chunk_y, self.chunks.len());
-            self.chunks.push([self.default_value; 1024]);
+        let index = match self.map.get(&chunk) {
+            Some(index) => *index,
+            None => {
+                self.map.insert(chunk, self.chunks.len());
+                self.chunks.push([self.default_value; 1024]);
+                self.origins.push((chunk.0 * CHUNK_SIZE, chunk.1 * CHUNK_SIZE));
 
-            self.chunks.last_mut().unwrap()
+                self.chunks.len() - 1
+            }
         };
 
-        if x < self.tl_x {
-            self.tl_x = x;
-        } else if x >= self.br_x {
-            self.br_x = x;
-        }
-        if y < self.tl_y {
-            self.tl_y = y;
-        } else if y >= self.br_y {
-            self.br_y = y;
+        self.bounds = Some(extend_bounds(self.bounds, x, y));
+
+        &mut self.chunks[index][relative_index]
+    }
+
+    pub fn set(&mut self, x: isize, y: isize, v: T) {
+        *self.get_mut(x, y) = v;
+    }
+
+    /// Goes over every cell in the chunks that have been written to, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (isize, isize, T)> + '_ {
+        self.chunks.iter().zip(self.origins.iter()).flat_map(|(chunk, (chunk_x, chunk_y))| {
+            chunk.iter().enumerate().map(move |(i, v)| {
+                (chunk_x + i as isize % CHUNK_SIZE, chunk_y + i as isize / CHUNK_SIZE, *v)
+            })
+        })
+    }
+
+    /// Like `iter`, but lets the cells be changed. Any of them might be, so the bounds grow to
+    /// cover every chunk; `shrink_to_fit` narrows them back down.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (isize, isize, &mut T)> {
+        for (chunk_x, chunk_y) in self.origins.iter() {
+            self.bounds = Some(extend_bounds(self.bounds, *chunk_x, *chunk_y));
+            self.bounds = Some(extend_bounds(self.bounds, chunk_x + CHUNK_SIZE - 1, chunk_y + CHUNK_SIZE - 1));
         }
 
-        &mut chunk[relative_index]
+        self.chunks.iter_mut().zip(self.origins.iter()).flat_map(|(chunk, (chunk_x, chunk_y))| {
+            chunk.iter_mut().enumerate().map(move |(i, v)| {
+                (chunk_x + i as isize % CHUNK_SIZE, chunk_y + i as isize / CHUNK_SIZE, v)
+            })
+        })
     }
 
-    pub fn bounds_tl(&self) -> (isize, isize) {
-        (self.tl_x, self.tl_y)
+    pub fn positions<'a>(&'a self, pred: impl Fn(T) -> bool + 'a) -> impl Iterator<Item = Point> + 'a {
+        self.iter().filter(move |(_, _, v)| pred(*v)).map(|(x, y, _)| (x, y))
     }
 
-    pub fn bounds_br(&self) -> (isize, isize) {
-        (self.br_x, self.br_y)
+    /// The top left and bottom right corners of the cells that have been written to, or `None`
+    /// if nothing has been.
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        self.bounds
     }
 
+    /// Copies the cells within the bounds into a `Grid` that uses the same coordinates.
     pub fn to_exact(&self) -> Grid<T> {
-        let offset_x = -self.tl_x;
-        let offset_y = -self.tl_y;
-        let width = ((self.br_x - self.tl_x) + 1) as usize;
-        let height = ((self.br_y - self.tl_y) + 1) as usize;
+        let ((tl_x, tl_y), (br_x, br_y)) = match self.bounds {
+            Some(bounds) => bounds,
+            None => return Grid::empty(self.default_value),
+        };
 
-        let mut grid = Grid::new(width, height, offset_x, offset_y, self.default_value);
+        let width = (br_x - tl_x + 1) as usize;
+        let height = (br_y - tl_y + 1) as usize;
+        let mut grid = Grid::new(width, height, -tl_x, -tl_y, self.default_value);
 
-        for x in self.tl_x..self.br_x {
-            for y in self.tl_y..self.br_y {
+        for y in tl_y..=br_y {
+            for x in tl_x..=br_x {
                 grid.set(x, y, self.get(x, y));
             }
         }
@@ -89,24 +115,60 @@ impl<T> BigGrid<T> where T: Clone + Copy + std::fmt::Debug {
         grid
     }
 
-    pub fn new(max_width: usize, max_height: usize, default_value: T) -> BigGrid<T> {
+    pub fn new(default_value: T) -> BigGrid<T> {
         BigGrid {
             chunks: Vec::with_capacity(128),
-            map: Grid::new(
-                max_width / 32, max_height / 32,
-                (max_width / 64) as isize, (max_height / 64) as isize,
-                0,
-            ),
+            origins: Vec::with_capacity(128),
+            map: HashMap::with_capacity(128),
             default_value,
-            tl_x: 0,
-            tl_y: 0,
-            br_x: 0,
-            br_y: 0,
+            bounds: None,
         }
     }
 }
 
-#[derive(Clone)]
+impl<T> BigGrid<T> where T: Clone + Copy + PartialEq + std::fmt::Debug {
+    /// Drops the chunks that only hold the default value and shrinks the bounds to the cells
+    /// that don't.
+    pub fn shrink_to_fit(&mut self) {
+        let mut bounds: Option<(Point, Point)> = None;
+        let mut chunks = Vec::with_capacity(self.chunks.len());
+        let mut origins = Vec::with_capacity(self.chunks.len());
+        self.map.clear();
+
+        for (chunk, origin) in self.chunks.iter().zip(self.origins.iter()) {
+            let mut used = false;
+
+            for (i, v) in chunk.iter().enumerate() {
+                if *v == self.default_value {
+                    continue;
+                }
+
+                let (x, y) = (origin.0 + i as isize % CHUNK_SIZE, origin.1 + i as isize / CHUNK_SIZE);
+                bounds = Some(extend_bounds(bounds, x, y));
+                used = true;
+            }
+
+            if used {
+                self.map.insert((origin.0.div_euclid(CHUNK_SIZE), origin.1.div_euclid(CHUNK_SIZE)), chunks.len());
+                chunks.push(*chunk);
+                origins.push(*origin);
+            }
+        }
+
+        self.chunks = chunks;
+        self.origins = origins;
+        self.bounds = bounds;
+    }
+}
+
+fn extend_bounds(bounds: Option<(Point, Point)>, x: isize, y: isize) -> (Point, Point) {
+    match bounds {
+        Some(((tl_x, tl_y), (br_x, br_y))) => ((tl_x.min(x), tl_y.min(y)), (br_x.max(x), br_y.max(y))),
+        None => ((x, y), (x, y)),
+    }
+}
+
+#[derive(Clone, Debug)]
 pub struct Grid<T> {
     data: Vec<T>,
     default_value: T,
@@ -123,6 +185,9 @@ impl<T> Grid<T> {
     pub fn data(&self) -> &[T] {
         &self.data
     }
+    pub fn default_value(&self) -> &T {
+        &self.default_value
+    }
 }
 
 impl<T> Grid<T> where T: Clone + Copy + std::fmt::Debug {
@@ -155,7 +220,7 @@ impl<T> Grid<T> where T: Clone + Copy + std::fmt::Debug {
             return self.default_value;
         }
 
-        self.data[self.index(x, y)]
+        self.data[(y as usize * self.width) + x as usize]
     }
 
     pub fn get_mut(&mut self, x: isize, y: isize) -> &mut T {
@@ -176,6 +241,148 @@ impl<T> Grid<T> where T: Clone + Copy + std::fmt::Debug {
         }
     }
 
+    /// Goes over the cells row by row, with the coordinates they are set and got with.
+    pub fn iter(&self) -> impl Iterator<Item = (isize, isize, T)> + '_ {
+        let (width, offset_x, offset_y) = (self.width.max(1), self.offset_x, self.offset_y);
+
+        self.data.iter().enumerate()
+            .map(move |(i, v)| ((i % width) as isize - offset_x, (i / width) as isize - offset_y, *v))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (isize, isize, &mut T)> {
+        let (width, offset_x, offset_y) = (self.width.max(1), self.offset_x, self.offset_y);
+
+        self.data.iter_mut().enumerate()
+            .map(move |(i, v)| ((i % width) as isize - offset_x, (i / width) as isize - offset_y, v))
+    }
+
+    /// The coordinates of the cells where `pred` holds.
+    pub fn positions<'a>(&'a self, pred: impl Fn(T) -> bool + 'a) -> impl Iterator<Item = Point> + 'a {
+        self.iter().filter(move |(_, _, v)| pred(*v)).map(|(x, y, _)| (x, y))
+    }
+
+    /// The four cells next to `(x, y)`, with the default value for the ones outside the grid.
+    pub fn neighbours(&self, x: isize, y: isize) -> impl Iterator<Item = (isize, isize, T)> + '_ {
+        IntoIterator::into_iter(orthogonal((x, y))).map(move |(x, y)| (x, y, self.get_oob(x, y)))
+    }
+
+    /// Like `neighbours`, but with the diagonals as well.
+    pub fn neighbours8(&self, x: isize, y: isize) -> impl Iterator<Item = (isize, isize, T)> + '_ {
+        IntoIterator::into_iter(surrounding((x, y))).map(move |(x, y)| (x, y, self.get_oob(x, y)))
+    }
+
+    /// Makes a `width` by `height` grid with the same top left corner, where `source` gives the
+    /// index in this grid of each column and row of the new one.
+    fn transformed(&self, width: usize, height: usize, source: impl Fn(usize, usize) -> usize) -> Grid<T> {
+        let mut data = Vec::with_capacity(width * height);
+        for row in 0..height {
+            for column in 0..width {
+                data.push(self.data[source(column, row)]);
+            }
+        }
+
+        Grid { data, width, height, ..*self }
+    }
+
+    /// Turns the grid a quarter turn clockwise. Like the other transformations, the top left
+    /// corner stays at the same coordinates.
+    pub fn rotate_right(&self) -> Grid<T> {
+        let (width, height) = (self.width, self.height);
+        self.transformed(height, width, |column, row| (height - 1 - column) * width + row)
+    }
+
+    pub fn rotate_left(&self) -> Grid<T> {
+        let (width, height) = (self.width, self.height);
+        self.transformed(height, width, |column, row| column * width + (width - 1 - row))
+    }
+
+    /// Mirrors the grid left to right.
+    pub fn flip_horizontal(&self) -> Grid<T> {
+        let width = self.width;
+        self.transformed(self.width, self.height, |column, row| row * width + (width - 1 - column))
+    }
+
+    /// Mirrors the grid top to bottom.
+    pub fn flip_vertical(&self) -> Grid<T> {
+        let (width, height) = (self.width, self.height);
+        self.transformed(width, height, |column, row| (height - 1 - row) * width + column)
+    }
+
+    /// Swaps rows and columns.
+    pub fn transpose(&self) -> Grid<T> {
+        let width = self.width;
+        self.transformed(self.height, self.width, |column, row| column * width + row)
+    }
+
+    /// Copies out the `width` by `height` cells from `(x, y)`. The cells keep their
+    /// coordinates, and the ones outside this grid get the default value.
+    pub fn crop(&self, (x, y): Point, width: usize, height: usize) -> Grid<T> {
+        let mut grid = Grid::new(width, height, -x, -y, self.default_value);
+        for row in 0..height as isize {
+            for column in 0..width as isize {
+                grid.set(x + column, y + row, self.get_oob(x + column, y + row));
+            }
+        }
+
+        grid
+    }
+
+    /// Adds `amount` cells with the default value on every side.
+    pub fn pad(&self, amount: usize) -> Grid<T> {
+        let (x, y) = (-self.offset_x - amount as isize, -self.offset_y - amount as isize);
+        self.crop((x, y), self.width + amount * 2, self.height + amount * 2)
+    }
+
+    /// Reads a grid with one row per line, turning each character into a cell with `cell`. All
+    /// rows must be as long as the first one.
+    pub fn from_lines(input: &str, default_value: T, mut cell: impl FnMut(char) -> Option<T>) -> Result<Grid<T>, ParseError> {
+        let mut data = Vec::with_capacity(input.len());
+        let mut width = 0;
+        let mut height = 0;
+
+        for (i, line) in input.trim_end_matches(['\n', '\r']).lines().enumerate() {
+            let error = |column, message: String| ParseError { line: i + 1, column, message };
+            let mut len = 0;
+
+            for c in line.chars() {
+                len += 1;
+                if height > 0 && len > width {
+                    return Err(error(len, format!("the row is longer than the first one ({})", width)));
+                }
+
+                data.push(cell(c).ok_or_else(|| error(len, format!("unexpected {:?}", c)))?);
+            }
+
+            if len == 0 {
+                return Err(error(1, "the row is empty".to_owned()));
+            }
+            if height > 0 && len < width {
+                return Err(error(len + 1, format!("the row is shorter than the first one ({})", width)));
+            }
+
+            width = len;
+            height += 1;
+        }
+
+        if height == 0 {
+            return Err(ParseError { line: 1, column: 1, message: "the grid is empty".to_owned() });
+        }
+
+        Ok(Grid { data, default_value, width, height, offset_x: 0, offset_y: 0 })
+    }
+
+    /// Draws the grid with one line per row.
+    pub fn render(&self, mut cell: impl FnMut(T) -> char) -> String {
+        let mut result = String::with_capacity((self.width + 1) * self.height);
+
+        for row in self.data.chunks(self.width.max(1)) {
+            result.extend(row.iter().map(|v| cell(*v)));
+            result.push('\n');
+        }
+
+        result
+    }
+
     pub fn empty(default_value: T) -> Grid<T> {
         Grid{
             width: 0,
@@ -188,6 +395,356 @@ impl<T> Grid<T> where T: Clone + Copy + std::fmt::Debug {
     }
 }
 
+impl Grid<char> {
+    pub fn from_str_lines(input: &str, default_value: char) -> Result<Grid<char>, ParseError> {
+        Grid::from_lines(input, default_value, Some)
+    }
+}
+
+impl Display for Grid<char> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.data.chunks(self.width.max(1)) {
+            for c in row {
+                f.write_char(*c)?;
+            }
+            f.write_char('\n')?;
+        }
+
+        Ok(())
+    }
+}
+
+pub type Point = (isize, isize);
+
+/// Read access to the cells of a grid, so the searches work on both `Grid` and `BigGrid`.
+pub trait Cells<T> {
+    fn cell(&self, point: Point) -> T;
+}
+
+impl<T> Cells<T> for Grid<T> where T: Clone + Copy + std::fmt::Debug {
+    fn cell(&self, (x, y): Point) -> T {
+        self.get_oob(x, y)
+    }
+}
+
+impl<T> Cells<T> for BigGrid<T> where T: Clone + Copy + std::fmt::Debug {
+    fn cell(&self, (x, y): Point) -> T {
+        self.get(x, y)
+    }
+}
+
+/// The four ways to go on a grid, where y grows downwards.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// Clockwise, starting with up.
+    pub const ALL: [Direction; 4] = [Direction::Up, Direction::Right, Direction::Down, Direction::Left];
+
+    pub fn turn_left(self) -> Direction {
+        Direction::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn turn_right(self) -> Direction {
+        Direction::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn reverse(self) -> Direction {
+        Direction::ALL[(self as usize + 2) % 4]
+    }
+
+    pub fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::Right => (1, 0),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+        }
+    }
+
+    pub fn step(self, (x, y): Point) -> Point {
+        let (dx, dy) = self.offset();
+
+        (x + dx, y + dy)
+    }
+
+    /// Reads `^`, `>`, `v` and `<`.
+    pub fn from_arrow(c: char) -> Option<Direction> {
+        match c {
+            '^' => Some(Direction::Up),
+            '>' => Some(Direction::Right),
+            'v' => Some(Direction::Down),
+            '<' => Some(Direction::Left),
+            _ => None,
+        }
+    }
+}
+
+pub fn orthogonal(point: Point) -> [Point; 4] {
+    Direction::ALL.map(|d| d.step(point))
+}
+
+/// The eight points around `(x, y)`, clockwise from the one above.
+pub fn surrounding((x, y): Point) -> [Point; 8] {
+    [
+        (x, y - 1), (x + 1, y - 1), (x + 1, y), (x + 1, y + 1),
+        (x, y + 1), (x - 1, y + 1), (x - 1, y), (x - 1, y - 1),
+    ]
+}
+
+pub fn manhattan((x1, y1): Point, (x2, y2): Point) -> u64 {
+    ((x2 - x1).unsigned_abs() + (y2 - y1).unsigned_abs()) as u64
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Path {
+    pub cost: u64,
+    /// Every point on the way, including the start and the goal.
+    pub points: Vec<Point>,
+}
+
+/// The distances from the start points to the points a search reached, along with the way back.
+pub struct Distances {
+    /// The distance to each point and the point before it. Start points come from themselves.
+    cells: HashMap<Point, (u64, Point)>,
+}
+
+impl Distances {
+    pub fn get(&self, point: Point) -> Option<u64> {
+        self.cells.get(&point).map(|(distance, _)| *distance)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, u64)> + '_ {
+        self.cells.iter().map(|(point, (distance, _))| (*point, *distance))
+    }
+
+    /// The point that is furthest away, and how far that is.
+    pub fn max(&self) -> Option<(Point, u64)> {
+        self.iter().max_by_key(|(point, distance)| (*distance, Reverse(*point)))
+    }
+
+    pub fn path_to(&self, goal: Point) -> Option<Path> {
+        let cost = self.get(goal)?;
+        let mut points = vec![goal];
+        let mut point = goal;
+
+        loop {
+            let previous = self.cells[&point].1;
+            if previous == point {
+                break;
+            }
+
+            points.push(previous);
+            point = previous;
+        }
+        points.reverse();
+
+        Some(Path { cost, points })
+    }
+}
+
+/// Finds the number of steps to every reachable point. `neighbours` says where a step can go
+/// from a point, and `passable` which cells can be stepped on.
+pub fn bfs<T, N>(grid: &impl Cells<T>, start: Point, passable: impl Fn(T) -> bool, neighbours: impl Fn(Point) -> N) -> Distances
+    where N: IntoIterator<Item = Point>
+{
+    flood_fill(grid, Some(start), passable, neighbours)
+}
+
+/// Spreads out from all of `sources` at once, and finds how many steps it takes to reach every
+/// point. On a `BigGrid`, the default value must not be passable or this never ends.
+pub fn flood_fill<T, N>(
+    grid: &impl Cells<T>, sources: impl IntoIterator<Item = Point>,
+    passable: impl Fn(T) -> bool, neighbours: impl Fn(Point) -> N,
+) -> Distances
+    where N: IntoIterator<Item = Point>
+{
+    let mut cells = HashMap::new();
+    let mut queue = VecDeque::new();
+
+    for source in sources {
+        cells.insert(source, (0, source));
+        queue.push_back((source, 0));
+    }
+
+    while let Some((point, distance)) = queue.pop_front() {
+        for next in neighbours(point) {
+            if !cells.contains_key(&next) && passable(grid.cell(next)) {
+                cells.insert(next, (distance + 1, point));
+                queue.push_back((next, distance + 1));
+            }
+        }
+    }
+
+    Distances { cells }
+}
+
+/// Connected groups of points, numbered from 0.
+pub struct Regions {
+    labels: HashMap<Point, usize>,
+    sizes: Vec<usize>,
+}
+
+impl Regions {
+    pub fn label(&self, point: Point) -> Option<usize> {
+        self.labels.get(&point).cloned()
+    }
+
+    /// The number of points in each region.
+    pub fn sizes(&self) -> &[usize] {
+        &self.sizes
+    }
+
+    pub fn len(&self) -> usize {
+        self.sizes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sizes.is_empty()
+    }
+}
+
+/// Splits `points` into regions, where two neighbouring points are in the same region if
+/// `connected` holds for their cells. Points that aren't in `points` are never entered.
+pub fn regions<T, N>(
+    grid: &impl Cells<T>, points: impl IntoIterator<Item = Point>,
+    connected: impl Fn(T, T) -> bool, neighbours: impl Fn(Point) -> N,
+) -> Regions
+    where T: Copy, N: IntoIterator<Item = Point>
+{
+    let points: Vec<Point> = points.into_iter().collect();
+    let mut labels: HashMap<Point, usize> = points.iter().map(|p| (*p, usize::MAX)).collect();
+    let mut sizes = Vec::new();
+    let mut stack = Vec::new();
+
+    for start in points {
+        if labels[&start] != usize::MAX {
+            continue;
+        }
+
+        let label = sizes.len();
+        let mut size = 1;
+        labels.insert(start, label);
+        stack.push(start);
+
+        while let Some(point) = stack.pop() {
+            let value = grid.cell(point);
+
+            for next in neighbours(point) {
+                if labels.get(&next) == Some(&usize::MAX) && connected(value, grid.cell(next)) {
+                    labels.insert(next, label);
+                    stack.push(next);
+                    size += 1;
+                }
+            }
+        }
+
+        sizes.push(size);
+    }
+
+    Regions { labels, sizes }
+}
+
+/// Finds the cheapest way to every reachable point, where `cost` is what it takes to step onto
+/// a cell, or `None` if it can't be.
+pub fn dijkstra<T, N>(grid: &impl Cells<T>, start: Point, cost: impl Fn(T) -> Option<u64>, neighbours: impl Fn(Point) -> N) -> Distances
+    where N: IntoIterator<Item = Point>
+{
+    search(grid, start, None, cost, neighbours, |_| 0)
+}
+
+/// Like `dijkstra`, but stops at `goal` and is guided there by `heuristic`, which must never
+/// overestimate the remaining cost.
+pub fn astar<T, N>(
+    grid: &impl Cells<T>, start: Point, goal: Point,
+    cost: impl Fn(T) -> Option<u64>, neighbours: impl Fn(Point) -> N, heuristic: impl Fn(Point) -> u64,
+) -> Option<Path>
+    where N: IntoIterator<Item = Point>
+{
+    search(grid, start, Some(goal), cost, neighbours, heuristic).path_to(goal)
+}
+
+fn search<T, N>(
+    grid: &impl Cells<T>, start: Point, goal: Option<Point>,
+    cost: impl Fn(T) -> Option<u64>, neighbours: impl Fn(Point) -> N, heuristic: impl Fn(Point) -> u64,
+) -> Distances
+    where N: IntoIterator<Item = Point>
+{
+    let mut cells = HashMap::new();
+    let mut queue = BinaryHeap::new();
+
+    cells.insert(start, (0, start));
+    queue.push(Reverse((heuristic(start), 0, start)));
+
+    while let Some(Reverse((_, distance, point))) = queue.pop() {
+        if distance > cells[&point].0 {
+            continue;
+        }
+        if goal == Some(point) {
+            break;
+        }
+
+        for next in neighbours(point) {
+            let step = match cost(grid.cell(next)) {
+                Some(step) => step,
+                None => continue,
+            };
+
+            let next_distance = distance + step;
+            if cells.get(&next).map(|(d, _)| next_distance < *d).unwrap_or(true) {
+                cells.insert(next, (next_distance, point));
+                queue.push(Reverse((next_distance + heuristic(next), next_distance, next)));
+            }
+        }
+    }
+
+    Distances { cells }
+}
+
+/// Dijkstra over any kind of state, for searches that need more than a point, like which keys
+/// have been picked up. `next` gives the states that can be reached from one and their cost.
+pub fn shortest_distance<S, I>(start: S, is_goal: impl Fn(&S) -> bool, mut next: impl FnMut(&S) -> I) -> Option<u64>
+    where S: Clone + Eq + std::hash::Hash + Ord, I: IntoIterator<Item = (S, u64)>
+{
+    let mut distances: HashMap<S, u64> = HashMap::new();
+    let mut queue = BinaryHeap::new();
+
+    distances.insert(start.clone(), 0);
+    queue.push(Reverse((0, start)));
+
+    while let Some(Reverse((distance, state))) = queue.pop() {
+        if distance > distances[&state] {
+            continue;
+        }
+        if is_goal(&state) {
+            return Some(distance);
+        }
+
+        for (next_state, cost) in next(&state) {
+            let next_distance = distance + cost;
+            if distances.get(&next_state).map(|d| next_distance < *d).unwrap_or(true) {
+                distances.insert(next_state.clone(), next_distance);
+                queue.push(Reverse((next_distance, next_state)));
+            }
+        }
+    }
+
+    None
+}
+
 pub fn grid_direction(x1: isize, y1: isize, x2: isize, y2: isize) -> (isize, isize) {
     let (dx, dy, _) = grid_direction_len(x1, y1, x2, y2);
 
@@ -216,12 +773,78 @@ pub fn direction_atan2(dx: isize, dy: isize) -> f64 {
     }
 }
 
-pub fn cmp_f64(a: f64, b: f64) -> Ordering {
-    let bi = (b * 1000.0) as i32;
+/// Orders directions clockwise, starting from straight up, with y growing downwards. It only
+/// uses integer math, so directions that are close together are still told apart.
+pub fn cmp_angle(a: (isize, isize), b: (isize, isize)) -> Ordering {
+    fn quadrant((dx, dy): (isize, isize)) -> u8 {
+        match (dx, dy) {
+            (0, 0) => 0,
+            (dx, dy) if dx >= 0 && dy < 0 => 1,
+            (dx, dy) if dx > 0 && dy >= 0 => 2,
+            (dx, dy) if dx <= 0 && dy > 0 => 3,
+            _ => 4,
+        }
+    }
+
+    // Within a quadrant, `a` comes first if `b` is clockwise from it.
+    let cross = a.0 as i128 * b.1 as i128 - a.1 as i128 * b.0 as i128;
 
-    ((a * 1000.0) as i32).cmp(&bi)
+    quadrant(a).cmp(&quadrant(b)).then(0.cmp(&cross))
 }
 
+/// The points that can be seen from `origin`, which is the nearest one in each direction, in
+/// clockwise order from straight up.
+pub fn visible_from(origin: Point, points: &[Point]) -> Vec<Point> {
+    let mut nearest: HashMap<(isize, isize), (isize, Point)> = HashMap::new();
+
+    for point in points.iter().cloned().filter(|p| *p != origin) {
+        let (dx, dy, distance) = grid_direction_len(origin.0, origin.1, point.0, point.1);
+        let entry = nearest.entry((dx, dy)).or_insert((distance, point));
+        if distance < entry.0 {
+            *entry = (distance, point);
+        }
+    }
+
+    let mut visible: Vec<((isize, isize), Point)> = nearest.into_iter().map(|(d, (_, p))| (d, p)).collect();
+    visible.sort_by(|(a, _), (b, _)| cmp_angle(*a, *b));
+
+    visible.into_iter().map(|(_, p)| p).collect()
+}
+
+/// How many points can be seen from `origin`, which is the number of directions they lie in.
+pub fn count_visible(origin: Point, points: &[Point]) -> usize {
+    points.iter()
+        .filter(|p| **p != origin)
+        .map(|p| grid_direction(origin.0, origin.1, p.0, p.1))
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+/// The order a beam turning clockwise from straight up around `origin` hits `points` in, when
+/// it only gets the nearest point left in a direction on each turn.
+pub fn rotation_order(origin: Point, points: &[Point]) -> Vec<Point> {
+    let mut targets: Vec<((isize, isize), isize, Point)> = points.iter().cloned()
+        .filter(|p| *p != origin)
+        .map(|p| {
+            let (dx, dy, distance) = grid_direction_len(origin.0, origin.1, p.0, p.1);
+            ((dx, dy), distance, p)
+        })
+        .collect();
+    targets.sort_by(|a, b| cmp_angle(a.0, b.0).then(a.1.cmp(&b.1)));
+
+    // The n-th point in a direction is hit on the n-th turn.
+    let mut turns: Vec<(usize, usize)> = Vec::with_capacity(targets.len());
+    for i in 0..targets.len() {
+        let turn = match turns.last() {
+            Some((turn, _)) if targets[i - 1].0 == targets[i].0 => turn + 1,
+            _ => 0,
+        };
+        turns.push((turn, i));
+    }
+    turns.sort_unstable();
+
+    turns.into_iter().map(|(_, i)| targets[i].2).collect()
+}
 
 #[cfg(test)]
 mod tests {
@@ -247,6 +870,243 @@ mod tests {
         assert_eq!(grid.get(3, 3), 0);
     }
 
+    #[test]
+    fn test_grid_lines() {
+        let grid = Grid::from_str_lines("#..\n.#.\r\n..#\n\n", ' ').unwrap();
+
+        assert_eq!(grid.size(), (3, 3));
+        assert_eq!(grid.get(2, 1), '.');
+        assert_eq!(grid.get(2, 2), '#');
+        assert_eq!(grid.to_string(), "#..\n.#.\n..#\n");
+        assert_eq!(grid.render(|c| if c == '#' { '1' } else { '0' }), "100\n010\n001\n");
+
+        let err = |input| Grid::from_str_lines(input, ' ').unwrap_err().to_string();
+        assert_eq!(err("..\n...\n"), "line 2, column 3: the row is longer than the first one (2)");
+        assert_eq!(err("...\n..\n"), "line 2, column 3: the row is shorter than the first one (3)");
+        assert_eq!(err("...\n\n...\n"), "line 2, column 1: the row is empty");
+        assert_eq!(err(""), "line 1, column 1: the grid is empty");
+
+        let err = Grid::from_lines("01\n2x\n", 0, |c| c.to_digit(10)).unwrap_err();
+        assert_eq!(err.to_string(), "line 2, column 2: unexpected 'x'");
+    }
+
+    #[test]
+    fn test_big_grid() {
+        let mut grid = BigGrid::new(0);
+        assert_eq!(grid.bounds(), None);
+        assert_eq!(grid.to_exact().size(), (0, 0));
+
+        grid.set(-33, -1, 1);
+        grid.set(-32, 0, 2);
+        grid.set(31, 32, 3);
+
+        assert_eq!(grid.get(-33, -1), 1);
+        assert_eq!(grid.get(-32, 0), 2);
+        assert_eq!(grid.get(31, 32), 3);
+        assert_eq!(grid.get(-1, -1), 0);
+        assert_eq!(grid.bounds(), Some(((-33, -1), (31, 32))));
+
+        let exact = grid.to_exact();
+        assert_eq!(exact.size(), (65, 34));
+        assert_eq!(exact.get(-33, -1), 1);
+        assert_eq!(exact.get(31, 32), 3);
+
+        grid.set(1_000_000, -1_000_000, 4);
+        assert_eq!(grid.get(1_000_000, -1_000_000), 4);
+
+        grid.set(1_000_000, -1_000_000, 0);
+        grid.set(31, 32, 0);
+        grid.shrink_to_fit();
+        assert_eq!(grid.bounds(), Some(((-33, -1), (-32, 0))));
+        assert_eq!(grid.iter().count(), 2048);
+        assert_eq!(grid.get(-32, 0), 2);
+    }
+
+    #[test]
+    fn test_grid_iter() {
+        let mut grid = Grid::new(3, 2, 1, 1, 0);
+        grid.set(-1, -1, 1);
+        grid.set(1, 0, 2);
+
+        let cells: Vec<_> = grid.iter().collect();
+        assert_eq!(cells.len(), 6);
+        assert_eq!(cells[0], (-1, -1, 1));
+        assert_eq!(cells[5], (1, 0, 2));
+        assert_eq!(grid.positions(|v| v > 0).collect::<Vec<_>>(), vec![(-1, -1), (1, 0)]);
+
+        let wanted = [2];
+        assert_eq!(grid.positions(|v| wanted.contains(&v)).collect::<Vec<_>>(), vec![(1, 0)]);
+
+        for (x, y, v) in grid.iter_mut() {
+            *v += (x * 10 + y) as i32;
+        }
+        assert_eq!(grid.get(-1, -1), -10);
+        assert_eq!(grid.get(1, 0), 12);
+
+        let mut big = BigGrid::new('.');
+        *big.get_mut(-40, 3) = '#';
+        *big.get_mut(5, 70) = '#';
+
+        assert_eq!(big.iter().count(), 2048);
+        let mut found: Vec<_> = big.positions(|c| c == '#').collect();
+        found.sort_unstable();
+        assert_eq!(found, vec![(-40, 3), (5, 70)]);
+
+        for (x, y, c) in big.iter_mut() {
+            if (x, y) == (-40, 3) {
+                *c = 'O';
+            } else if (x, y) == (-33, 31) {
+                *c = '#';
+            }
+        }
+        assert_eq!(big.get(-40, 3), 'O');
+        assert_eq!(big.get(-33, 31), '#');
+        assert_eq!(big.get(5, 70), '#');
+        assert_eq!(big.bounds(), Some(((-64, 0), (31, 95))));
+        big.shrink_to_fit();
+        assert_eq!(big.bounds(), Some(((-40, 3), (5, 70))));
+    }
+
+    #[test]
+    fn test_directions() {
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Left.turn_right(), Direction::Up);
+        assert_eq!(Direction::Right.reverse(), Direction::Left);
+        assert_eq!(Direction::Down.step((3, 3)), (3, 4));
+        assert_eq!(Direction::from_arrow('<'), Some(Direction::Left));
+
+        for d in Direction::ALL.iter() {
+            assert_eq!(d.turn_left().turn_left(), d.reverse());
+            assert_eq!(d.turn_right().turn_left(), *d);
+        }
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let mut grid = Grid::new(3, 3, 1, 1, 0);
+        for (i, (x, y)) in surrounding((0, 0)).iter().enumerate() {
+            grid.set(*x, *y, i + 1);
+        }
+
+        assert_eq!(grid.neighbours(0, 0).map(|(_, _, v)| v).collect::<Vec<_>>(), vec![1, 3, 5, 7]);
+        assert_eq!(grid.neighbours8(0, 0).map(|(_, _, v)| v).sum::<usize>(), 36);
+        assert_eq!(grid.neighbours(1, 1).collect::<Vec<_>>(), vec![(1, 0, 3), (2, 1, 0), (1, 2, 0), (0, 1, 5)]);
+    }
+
+    const MAZE: &str = "\
+#########
+#S..#...#
+#.#.#.#.#
+#.#...#G#
+#########
+";
+
+    #[test]
+    fn test_bfs() {
+        let grid = Grid::from_str_lines(MAZE, '#').unwrap();
+        let distances = bfs(&grid, (1, 1), |c| c != '#', orthogonal);
+
+        assert_eq!(distances.get((7, 3)), Some(12));
+        assert_eq!(distances.get((1, 3)), Some(2));
+        assert_eq!(distances.get((0, 0)), None);
+        assert_eq!(distances.len(), 15);
+
+        let path = distances.path_to((7, 3)).unwrap();
+        assert_eq!(path.cost, 12);
+        assert_eq!(path.points.len(), 13);
+        assert_eq!(path.points.first(), Some(&(1, 1)));
+        assert_eq!(path.points.last(), Some(&(7, 3)));
+        assert!(path.points.windows(2).all(|w| manhattan(w[0], w[1]) == 1));
+    }
+
+    #[test]
+    fn test_weighted_search() {
+        let grid = Grid::from_lines("1163\n1381\n2136\n", 0, |c| c.to_digit(10).map(|d| d as u64)).unwrap();
+        let cost = |c| Some(c).filter(|c| *c > 0);
+
+        let distances = dijkstra(&grid, (0, 0), cost, orthogonal);
+        assert_eq!(distances.get((3, 2)), Some(13));
+
+        let path = astar(&grid, (0, 0), (3, 2), cost, orthogonal, |p| manhattan(p, (3, 2))).unwrap();
+        assert_eq!(path.cost, 13);
+        assert_eq!(path.points, vec![(0, 0), (0, 1), (0, 2), (1, 2), (2, 2), (3, 2)]);
+
+        let mut big = BigGrid::new('.');
+        for y in -5..5 {
+            *big.get_mut(0, y) = '#';
+        }
+        let path = astar(&big, (-2, 0), (2, 0), |c| Some(1).filter(|_| c != '#'), orthogonal, |p| manhattan(p, (2, 0))).unwrap();
+        assert_eq!(path.cost, 14);
+        assert_eq!(astar(&grid, (0, 0), (9, 9), cost, orthogonal, |_| 0), None);
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let grid = Grid::from_str_lines(MAZE, '#').unwrap();
+        let distances = flood_fill(&grid, vec![(1, 1), (7, 3)], |c| c != '#', orthogonal);
+
+        assert_eq!(distances.len(), 15);
+        assert_eq!(distances.get((7, 1)), Some(2));
+        assert_eq!(distances.max(), Some(((5, 3), 6)));
+        assert_eq!(distances.path_to((5, 1)).unwrap().points, vec![(7, 3), (7, 2), (7, 1), (6, 1), (5, 1)]);
+
+        let mut big = BigGrid::new('#');
+        for (x, y) in grid.positions(|c| c != '#') {
+            big.set(x - 20, y - 20, '.');
+        }
+        assert_eq!(flood_fill(&big, Some((-19, -19)), |c| c == '.', orthogonal).max().unwrap().1, 12);
+    }
+
+    #[test]
+    fn test_regions() {
+        let grid = Grid::from_str_lines("aab\nbab\nbba\n", ' ').unwrap();
+        let found = regions(&grid, grid.positions(|_| true), |a, b| a == b, orthogonal);
+
+        assert_eq!(found.len(), 4);
+        assert_eq!(found.sizes(), &[3, 2, 3, 1]);
+        assert_eq!(found.label((0, 0)), found.label((1, 1)));
+        assert_ne!(found.label((2, 2)), found.label((1, 1)));
+
+        let mut big = BigGrid::new('.');
+        big.set(-1, 0, '#');
+        big.set(0, 0, '#');
+        big.set(5, 5, '#');
+        let found = regions(&big, big.positions(|c| c == '#'), |_, _| true, orthogonal);
+        assert_eq!(found.len(), 2);
+    }
+
+    #[test]
+    fn test_transformations() {
+        let grid = Grid::from_str_lines("abc\ndef\n", '.').unwrap();
+
+        assert_eq!(grid.rotate_right().to_string(), "da\neb\nfc\n");
+        assert_eq!(grid.rotate_left().to_string(), "cf\nbe\nad\n");
+        assert_eq!(grid.flip_horizontal().to_string(), "cba\nfed\n");
+        assert_eq!(grid.flip_vertical().to_string(), "def\nabc\n");
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf\n");
+        assert_eq!(grid.rotate_right().rotate_right().to_string(), grid.flip_horizontal().flip_vertical().to_string());
+        assert_eq!(grid.rotate_left().rotate_right().data(), grid.data());
+
+        let mut offset = Grid::new(2, 2, 5, 5, 0);
+        offset.set(-5, -5, 1);
+        offset.set(-4, -5, 2);
+        let rotated = offset.rotate_right();
+        assert_eq!(rotated.get(-5, -5), 0);
+        assert_eq!(rotated.get(-4, -5), 1);
+        assert_eq!(rotated.get(-4, -4), 2);
+
+        let padded = grid.pad(1);
+        assert_eq!(padded.size(), (5, 4));
+        assert_eq!(padded.get(0, 0), 'a');
+        assert_eq!(padded.get(-1, -1), '.');
+        assert_eq!(padded.to_string(), ".....\n.abc.\n.def.\n.....\n");
+
+        let cropped = padded.crop((1, 0), 3, 2);
+        assert_eq!(cropped.to_string(), "bc.\nef.\n");
+        assert_eq!(cropped.get(1, 0), 'b');
+        assert_eq!(cropped.get(3, 1), '.');
+    }
+
     #[test]
     fn test_grid_direction() {
         assert_eq!(grid_direction(0, 0, 6, 3), (2, 1));
@@ -255,6 +1115,28 @@ mod tests {
         assert_eq!(grid_direction(0, 0, -1, -5), (-1, -5));
     }
 
+    #[test]
+    fn test_cmp_angle() {
+        let mut directions = vec![(-1, 0), (0, 1), (1, 0), (0, -1), (-1, -1), (1, 1), (1, -1), (-1, 1)];
+        directions.sort_by(|a, b| cmp_angle(*a, *b));
+        assert_eq!(directions, vec![(0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1)]);
+
+        // Far closer together than a thousandth of a degree, which is all floats rounded to it can tell apart.
+        assert_eq!(cmp_angle((100_000_000, -100_000_001), (100_000_001, -100_000_002)), Ordering::Less);
+        assert_eq!(cmp_angle((100_000_001, -100_000_002), (100_000_000, -100_000_001)), Ordering::Greater);
+        assert_eq!(cmp_angle((2, 4), (1, 2)), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_visibility() {
+        let points = vec![(0, 0), (1, 0), (2, 0), (0, 2), (-1, -1), (-2, -2), (1, -3)];
+
+        assert_eq!(visible_from((0, 0), &points), vec![(1, -3), (1, 0), (0, 2), (-1, -1)]);
+        assert_eq!(count_visible((0, 0), &points), 4);
+        assert_eq!(count_visible((2, 0), &points), 5);
+        assert_eq!(rotation_order((0, 0), &points), vec![(1, -3), (1, 0), (0, 2), (-1, -1), (2, 0), (-2, -2)]);
+    }
+
     #[test]
     fn test_grid_direction_atan2() {
         assert_eq!(direction_atan2(0, -1), 0.0);
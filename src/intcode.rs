@@ -1,3 +1,5 @@
+use crate::parse::{ParseError, Scanner};
+
 #[derive(Clone)]
 pub struct VM {
     initial_program: Vec<i64>,
@@ -280,9 +282,12 @@ impl VM {
         }
     }
 
-    pub fn parse(program_data: &str) -> VM {
-        let data: Vec<i64> = program_data.split(',').map(|t| t.parse::<i64>().unwrap()).collect();
-        Self::new(&data)
+    pub fn parse(program_data: &str) -> Result<VM, ParseError> {
+        let mut scanner = Scanner::new(program_data);
+        let data: Vec<i64> = scanner.separated(b',', |s| s.signed())?;
+        scanner.end()?;
+
+        Ok(Self::new(&data))
     }
 }
 
@@ -296,7 +301,7 @@ mod tests {
 
     #[test]
     fn test_vm() {
-        let mut vm = VM::parse("103,13,1001,13,5,13,1002,13,14,14,4,14,99,5,5");
+        let mut vm = VM::parse("103,13,1001,13,5,13,1002,13,14,14,4,14,99,5,5").unwrap();
         vm.reset();
 
         assert_eq!(vm.step(), StepResult::InputRequired);
@@ -315,37 +320,37 @@ mod tests {
 
     #[test]
     fn test_day05_part2() {
-        let mut vm1 = VM::parse("3,9,8,9,10,9,4,9,99,-1,8");
+        let mut vm1 = VM::parse("3,9,8,9,10,9,4,9,99,-1,8").unwrap();
         assert_eq!(vm1.quick_run(&[8]), 1);
         assert_eq!(vm1.quick_run(&[7]), 0);
         assert_eq!(vm1.quick_run(&[9]), 0);
 
-        let mut vm2 = VM::parse("3,9,7,9,10,9,4,9,99,-1,8");
+        let mut vm2 = VM::parse("3,9,7,9,10,9,4,9,99,-1,8").unwrap();
         assert_eq!(vm2.quick_run(&[8]), 0);
         assert_eq!(vm2.quick_run(&[7]), 1);
         assert_eq!(vm2.quick_run(&[9]), 0);
 
-        let mut vm3 = VM::parse("3,3,1108,-1,8,3,4,3,99");
+        let mut vm3 = VM::parse("3,3,1108,-1,8,3,4,3,99").unwrap();
         assert_eq!(vm3.quick_run(&[8]), 1);
         assert_eq!(vm3.quick_run(&[7]), 0);
         assert_eq!(vm3.quick_run(&[9]), 0);
 
-        let mut vm4 = VM::parse("3,3,1107,-1,8,3,4,3,99");
+        let mut vm4 = VM::parse("3,3,1107,-1,8,3,4,3,99").unwrap();
         assert_eq!(vm4.quick_run(&[8]), 0);
         assert_eq!(vm4.quick_run(&[7]), 1);
         assert_eq!(vm4.quick_run(&[9]), 0);
 
-        let mut vm5 = VM::parse("3,12,6,12,15,1,13,14,13,4,13,99,-1,0,1,9");
+        let mut vm5 = VM::parse("3,12,6,12,15,1,13,14,13,4,13,99,-1,0,1,9").unwrap();
         assert_eq!(vm5.quick_run(&[0]), 0);
         assert_eq!(vm5.quick_run(&[-1]), 1);
         assert_eq!(vm5.quick_run(&[1]), 1);
 
-        let mut vm6 = VM::parse("3,3,1105,-1,9,1101,0,0,12,4,12,99,1");
+        let mut vm6 = VM::parse("3,3,1105,-1,9,1101,0,0,12,4,12,99,1").unwrap();
         assert_eq!(vm6.quick_run(&[0]), 0);
         assert_eq!(vm6.quick_run(&[-1]), 1);
         assert_eq!(vm6.quick_run(&[1]), 1);
 
-        let mut vm7 = VM::parse("3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31,1106,0,36,98,0,0,1002,21,125,20,4,20,1105,1,46,104,999,1105,1,46,1101,1000,1,20,4,20,1105,1,46,98,99");
+        let mut vm7 = VM::parse("3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31,1106,0,36,98,0,0,1002,21,125,20,4,20,1105,1,46,104,999,1105,1,46,1101,1000,1,20,4,20,1105,1,46,98,99").unwrap();
         assert_eq!(vm7.quick_run(&[-4]), 999);
         assert_eq!(vm7.quick_run(&[8]), 1000);
         assert_eq!(vm7.quick_run(&[14]), 1001);
@@ -353,20 +358,20 @@ mod tests {
 
     #[test]
     fn test_day09_part1() {
-        let mut vm1 = VM::parse("109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99");
+        let mut vm1 = VM::parse("109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99").unwrap();
         assert_eq!(vm1.run(), StepResult::Exit);
         assert_eq!(vm1.output(), &[109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99]);
 
-        let mut vm3 = VM::parse("1102,34915192,34915192,7,4,7,99,0");
+        let mut vm3 = VM::parse("1102,34915192,34915192,7,4,7,99,0").unwrap();
         assert!(vm3.quick_run(&[]) >= 1000000000000000);
 
-        let mut vm3 = VM::parse("104,1125899906842624,99");
+        let mut vm3 = VM::parse("104,1125899906842624,99").unwrap();
         assert_eq!(vm3.quick_run(&[]), 1125899906842624);
     }
 
     #[test]
     fn test_read_output() {
-        let mut vm = VM::parse("104,1,104,2,104,3,99");
+        let mut vm = VM::parse("104,1,104,2,104,3,99").unwrap();
 
         assert_eq!(vm.run(), StepResult::Exit);
         assert_eq!(vm.read_output(), &[1, 2, 3]);
@@ -1,6 +1,14 @@
 extern crate num;
 
 pub mod aoc;
+pub mod automaton;
+pub mod bench;
+pub mod fetch;
 pub mod intcode;
 pub mod math;
-pub mod grid;
\ No newline at end of file
+pub mod parse;
+pub mod grid;
+pub mod maze;
+pub mod pool;
+pub mod scaffold;
+pub mod days;
\ No newline at end of file
@@ -0,0 +1,446 @@
+use std::env;
+use std::fs;
+use std::process;
+use std::time::{Duration, Instant};
+use chrono::Utc;
+use common::aoc::{Baseline, CSV_HEADER, OutputFormat, RunOptions, Report, baseline_path, example_name, read_input,
+                  markdown_report, print_comparisons, print_report_as, print_summary, print_time_shares, verify_report};
+use common::days::registry;
+use common::pool::{available_threads, run_ordered};
+use common::fetch::{DEFAULT_BASE_URL, Fetched, Fetcher, load_session};
+use common::scaffold::new_day;
+
+const USAGE: &str = "Usage: aoc <run|bench> <day|all|from..to> [--part <1|2>] [--verify]
+                [--warmup <ms>] [--min-time <ms>] [--save-baseline] [--compare]
+                [--threshold <percent>] [--format <text|json|csv>]
+                [--input <path|->] [--example <name>] [--serial] [--timeout <ms>]
+       aoc report [day|all|from..to] [--output <path|->] [bench options]
+       aoc fetch [day|all|from..to] [--base-url <url>]
+       aoc new <day>
+
+Commands:
+  run    Run each part once and print the answers.
+  bench  Warm up for --warmup ms (default 50), then run each part at least
+         as many times as the day asks for and for at least --min-time ms
+         (default 100). Prints the median and the spread of the runs.
+  report Benchmark the days (all by default) like bench does, and write a
+         Markdown table of the answer hashes and medians to --output
+         (default ./report.md). Alternative methods are also compared in
+         a table for each part that has them.
+  fetch  Download the inputs (all by default) that are unlocked and not in
+         the input directory yet. The session cookie is read from
+         $AOC_SESSION or from AOC_SESSION=... in cookie.env.
+  new    Create src/days/dayXX.rs, add it to the registry and create empty
+         input and answer files for it.
+
+Days are given as a number (7), a range including both ends (3..10) or all.
+They run in parallel, one per thread, unless --serial is given, which gives
+steadier benchmarks. Each day's output is printed in order once it is done.
+A part that panics or takes longer than --timeout ms (default 60000, 0 for
+no limit) is reported as PANIC or TIMEOUT, and the exit code is 1.
+
+Inputs are read from the --input file (- for stdin, single day only), or
+else from dayXX.txt in $AOC_INPUT_DIR, or else in the project's input/
+directory. --example example1 reads dayXX.example1.txt from there instead.
+
+With --verify, the answers are compared with the project's
+answers/dayXX.pN.txt and the exit code is 1 if any of them are wrong
+or missing.
+
+With --format json or csv, every result and timing is printed as a JSON
+object or CSV row, with times in nanoseconds. Anything else goes to stderr.
+
+Baselines (bench only):
+  --save-baseline  Store the medians in the project's bench-baseline.tsv.
+  --compare        Compare the medians with the project's baseline. Parts that
+                   got slower by more than --threshold percent (default 10)
+                   are regressions, and the exit code is 1.";
+
+struct Args {
+    days: Vec<u32>,
+    options: RunOptions,
+    verify: bool,
+    save_baseline: bool,
+    compare: bool,
+    threshold: f64,
+    format: OutputFormat,
+    input: Option<String>,
+    example: Option<String>,
+    serial: bool,
+    output: Option<String>,
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    match args.first().map(|s| s.as_str()) {
+        Some("fetch") => return fetch(&args[1..]),
+        Some("new") => return new(&args[1..]),
+        _ => {}
+    }
+
+    let Args { days, options, verify, save_baseline, compare, threshold, format, input, example, serial, output } = match parse_args(&args) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let registry = registry();
+    let mut reports: Vec<Report> = Vec::with_capacity(days.len());
+    let mut wrong_answers = 0;
+    let mut unrecorded = 0;
+    let mut regressions = 0;
+    let mut failures = 0;
+
+    let mut baseline = if save_baseline || compare {
+        match Baseline::load(&baseline_path()) {
+            Ok(baseline) => baseline,
+            Err(e) => {
+                eprintln!("Could not read {}: {}", baseline_path().display(), e);
+                process::exit(1);
+            }
+        }
+    } else {
+        Baseline::default()
+    };
+
+    let text = format == OutputFormat::Text;
+    if format == OutputFormat::Csv {
+        println!("{}", CSV_HEADER);
+    }
+
+    let mut jobs = Vec::with_capacity(days.len());
+    for day in days.iter().cloned() {
+        let entry = match registry.iter().find(|d| d.day == day) {
+            Some(entry) => entry,
+            None => {
+                if days.len() == 1 {
+                    eprintln!("Day {} is not implemented.", day);
+                    process::exit(1);
+                }
+
+                continue;
+            }
+        };
+
+        let name = match &example {
+            Some(example) => example_name(&entry.name(), example),
+            None => entry.name(),
+        };
+
+        match read_input(&name, input.as_deref()) {
+            Ok(input) => jobs.push((entry, input)),
+            Err(e) if e.is_not_found() && days.len() > 1 => {
+                eprintln!("Skipping day {}: {}", day, e);
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(1);
+            }
+        }
+    }
+
+    let threads = if serial { 1 } else { available_threads().min(jobs.len()).max(1) };
+    let start = Instant::now();
+
+    run_ordered(&jobs, threads, |(entry, input)| (entry.run)(input, &options), |_, report| {
+        if text {
+            println!("--- Day {} ---", report.day);
+        }
+        print_report_as(&report, format);
+        failures += report.failures();
+
+        if verify {
+            for mismatch in verify_report(&report) {
+                eprintln!("{}", mismatch);
+                if mismatch.is_unrecorded() {
+                    unrecorded += 1;
+                } else {
+                    wrong_answers += 1;
+                }
+            }
+        }
+
+        if compare {
+            let comparisons = baseline.compare(&report, threshold);
+            regressions += comparisons.iter().filter(|c| c.regression).count();
+            if text {
+                print_comparisons(&comparisons);
+            }
+        }
+
+        if save_baseline {
+            baseline.record(&report);
+        }
+
+        if text {
+            println!();
+        }
+
+        reports.push(report);
+    });
+
+    let wall_ns = start.elapsed().as_nanos() as i64;
+
+    if text && reports.len() > 1 {
+        print_summary(&reports);
+        print_time_shares(&reports, wall_ns, threads);
+    }
+
+    if let Some(output) = output {
+        let markdown = markdown_report(&reports);
+
+        if output == "-" {
+            print!("{}", markdown);
+        } else if let Err(e) = fs::write(&output, markdown) {
+            eprintln!("Could not write {}: {}", output, e);
+            process::exit(1);
+        } else {
+            eprintln!("Wrote {}", output);
+        }
+    }
+
+    if save_baseline {
+        if let Err(e) = baseline.save(&baseline_path()) {
+            eprintln!("Could not write {}: {}", baseline_path().display(), e);
+            process::exit(1);
+        }
+    }
+
+    if wrong_answers > 0 {
+        eprintln!("{} wrong answer(s).", wrong_answers);
+    }
+
+    if unrecorded > 0 {
+        eprintln!("{} answer(s) not recorded, so not verified.", unrecorded);
+    }
+
+    if regressions > 0 {
+        eprintln!("{} regression(s).", regressions);
+    }
+
+    if failures > 0 {
+        eprintln!("{} failed part(s).", failures);
+    }
+
+    if wrong_answers > 0 || unrecorded > 0 || regressions > 0 || failures > 0 {
+        process::exit(1);
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<Args, String> {
+    let mut options = RunOptions {
+        timeout: Some(Duration::from_secs(60)),
+        ..RunOptions::default()
+    };
+    let mut verify = false;
+    let mut save_baseline = false;
+    let mut compare = false;
+    let mut threshold = 10.0;
+    let mut format = OutputFormat::default();
+    let mut input = None;
+    let mut example = None;
+    let mut serial = false;
+
+    match args.first().map(|s| s.as_str()) {
+        Some("run") => {}
+        Some("bench") | Some("report") => {
+            options.bench = true;
+            options.warmup = Duration::from_millis(50);
+            options.min_duration = Duration::from_millis(100);
+        }
+        Some(command) => return Err(format!("Unknown command: {}", command)),
+        None => return Err("No command given.".to_owned()),
+    }
+
+    let report = args[0] == "report";
+    let mut output = if report { Some("./report.md".to_owned()) } else { None };
+
+    let (days, skip) = match args.get(1) {
+        Some(days) if !days.starts_with("--") => (parse_days(days)?, 2),
+        _ if report => (parse_days("all")?, 1),
+        _ => return Err("No days given.".to_owned()),
+    };
+
+    let mut rest = args.iter().skip(skip);
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--part" => {
+                options.part = match rest.next().map(|s| s.as_str()) {
+                    Some("1") => Some(1),
+                    Some("2") => Some(2),
+                    _ => return Err("--part must be 1 or 2.".to_owned()),
+                };
+            }
+            "--verify" => verify = true,
+            "--warmup" => options.warmup = parse_ms(rest.next())?,
+            "--min-time" => options.min_duration = parse_ms(rest.next())?,
+            "--format" => format = rest.next().ok_or("--format needs a format.")?.parse()?,
+            "--input" => input = Some(rest.next().ok_or("--input needs a path.")?.clone()),
+            "--example" => example = Some(rest.next().ok_or("--example needs a name.")?.clone()),
+            "--serial" => serial = true,
+            "--output" if report => output = Some(rest.next().ok_or("--output needs a path.")?.clone()),
+            "--timeout" => {
+                let timeout = parse_ms(rest.next())?;
+                options.timeout = if timeout.as_millis() > 0 { Some(timeout) } else { None };
+            }
+            "--save-baseline" => save_baseline = true,
+            "--compare" => compare = true,
+            "--threshold" => {
+                threshold = match rest.next().map(|s| s.parse::<f64>()) {
+                    Some(Ok(percent)) if percent >= 0.0 => percent,
+                    _ => return Err("--threshold must be a positive percentage.".to_owned()),
+                };
+            }
+            _ => return Err(format!("Unknown argument: {}", arg)),
+        }
+    }
+
+    if (save_baseline || compare) && !options.bench {
+        return Err("--save-baseline and --compare only work with bench.".to_owned());
+    }
+
+    if input.is_some() && days.len() > 1 {
+        return Err("--input only works with a single day.".to_owned());
+    }
+    if input.is_some() && example.is_some() {
+        return Err("--input and --example can't be used together.".to_owned());
+    }
+    if example.is_some() && (verify || save_baseline || compare) {
+        return Err("--example can't be used with --verify, --save-baseline or --compare.".to_owned());
+    }
+
+    Ok(Args { days, options, verify, save_baseline, compare, threshold, format, input, example, serial, output })
+}
+
+fn fetch(args: &[String]) {
+    let (days, base_url) = match parse_fetch_args(args) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let session = match load_session() {
+        Some(session) => session,
+        None => {
+            eprintln!("No session cookie found. Set AOC_SESSION or add it to cookie.env.");
+            process::exit(1);
+        }
+    };
+
+    let fetcher = Fetcher::new(&base_url, &session);
+    let now = Utc::now();
+    let mut errors = 0;
+
+    for day in days {
+        match fetcher.fetch(day, now) {
+            Ok(Fetched::Downloaded) => println!("Day {}: downloaded.", day),
+            Ok(Fetched::Cached) => println!("Day {}: already downloaded.", day),
+            Ok(Fetched::Locked) => println!("Day {}: not unlocked yet.", day),
+            Err(e) => {
+                eprintln!("{}", e);
+                errors += 1;
+            }
+        }
+    }
+
+    if errors > 0 {
+        process::exit(1);
+    }
+}
+
+fn new(args: &[String]) {
+    let day = match args {
+        [day] => parse_day(day),
+        _ => Err("new takes a single day.".to_owned()),
+    };
+    let day = match day {
+        Ok(day) => day,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+
+    match new_day(day) {
+        Ok(files) => {
+            for file in files {
+                println!("Wrote {}", file.display());
+            }
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn parse_fetch_args(args: &[String]) -> Result<(Vec<u32>, String), String> {
+    let mut days = None;
+    let mut base_url = DEFAULT_BASE_URL.to_owned();
+
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--base-url" => base_url = rest.next().ok_or("--base-url needs a URL.")?.clone(),
+            _ if days.is_none() => days = Some(parse_days(arg)?),
+            _ => return Err(format!("Unknown argument: {}", arg)),
+        }
+    }
+
+    Ok((days.unwrap_or_else(|| (1..=25).collect()), base_url))
+}
+
+fn parse_ms(s: Option<&String>) -> Result<Duration, String> {
+    match s.map(|s| s.parse::<u64>()) {
+        Some(Ok(ms)) => Ok(Duration::from_millis(ms)),
+        _ => Err("Expected a duration in milliseconds.".to_owned()),
+    }
+}
+
+fn parse_days(s: &str) -> Result<Vec<u32>, String> {
+    let (from, to) = if s == "all" {
+        (1, 25)
+    } else if let Some(index) = s.find("..") {
+        let to = s[index + 2..].trim_start_matches('=');
+
+        (parse_day(&s[..index])?, parse_day(to)?)
+    } else {
+        let day = parse_day(s)?;
+
+        (day, day)
+    };
+
+    if from > to {
+        return Err(format!("Empty range of days: {}", s));
+    }
+
+    Ok((from..=to).collect())
+}
+
+fn parse_day(s: &str) -> Result<u32, String> {
+    match s.parse::<u32>() {
+        Ok(day) if (1..=25).contains(&day) => Ok(day),
+        _ => Err(format!("Invalid day: {}", s)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_days() {
+        assert_eq!(parse_days("7"), Ok(vec![7]));
+        assert_eq!(parse_days("3..6"), Ok(vec![3, 4, 5, 6]));
+        assert_eq!(parse_days("3..=6"), Ok(vec![3, 4, 5, 6]));
+        assert_eq!(parse_days("all").unwrap().len(), 25);
+        assert!(parse_days("0").is_err());
+        assert!(parse_days("26").is_err());
+        assert!(parse_days("10..3").is_err());
+    }
+}
@@ -8,15 +8,16 @@ impl<T> Permutations<T> where T : Clone + Copy + std::fmt::Debug {
     pub fn count(&mut self) -> usize {
         let mut count = 0;
 
-        while let Some(_) = self.next() {
+        while self.next().is_some() {
             count += 1;
         }
 
         count
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> Option<&[T]> {
-        if self.stack.len() == 0 {
+        if self.stack.is_empty() {
             return None
         }
 
@@ -0,0 +1,519 @@
+use std::collections::HashMap;
+use crate::grid::{bfs, orthogonal, shortest_distance, Grid, Point};
+use crate::parse::ParseError;
+
+const START: &str = "AA";
+const END: &str = "ZZ";
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Portal {
+    pub label: String,
+    /// The open cell next to the label, which is where the portal is entered and left.
+    pub position: Point,
+    /// Whether the portal is on the outside edge of the donut rather than around the hole.
+    pub outer: bool,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Edge {
+    pub to: usize,
+    pub cost: u64,
+    /// How the level changes when going this way: 1 for inner portals, -1 for outer ones.
+    pub depth: isize,
+}
+
+/// A donut-shaped maze with two-letter portals, turned into a graph between the portals.
+#[derive(Debug)]
+pub struct DonutMaze {
+    grid: Grid<char>,
+    portals: Vec<Portal>,
+    edges: Vec<Vec<Edge>>,
+    start: usize,
+    end: usize,
+}
+
+impl DonutMaze {
+    pub fn parse(input: &str) -> Result<DonutMaze, ParseError> {
+        let grid = parse_padded(input);
+        let portals = find_portals(&grid)?;
+
+        let find = |label: &str| portals.iter().position(|p| p.label == label)
+            .ok_or_else(|| error_at((0, 0), format!("there is no {} portal", label)));
+        let start = find(START)?;
+        let end = find(END)?;
+
+        let mut edges: Vec<Vec<Edge>> = vec![Vec::new(); portals.len()];
+        for (i, portal) in portals.iter().enumerate() {
+            let distances = bfs(&grid, portal.position, |c| c == '.', orthogonal);
+
+            for (j, other) in portals.iter().enumerate() {
+                match distances.get(other.position) {
+                    Some(cost) if i != j => edges[i].push(Edge { to: j, cost, depth: 0 }),
+                    _ => {}
+                }
+
+                if i != j && other.label == portal.label {
+                    edges[i].push(Edge { to: j, cost: 1, depth: if portal.outer { -1 } else { 1 } });
+                }
+            }
+        }
+
+        Ok(DonutMaze { grid, portals, edges, start, end })
+    }
+
+    pub fn grid(&self) -> &Grid<char> {
+        &self.grid
+    }
+
+    pub fn portals(&self) -> &[Portal] {
+        &self.portals
+    }
+
+    /// The ways out of a portal, both through the corridors and through the portal itself.
+    pub fn edges(&self, portal: usize) -> &[Edge] {
+        &self.edges[portal]
+    }
+
+    /// The fewest steps from `AA` to `ZZ`, with each portal leading straight to its pair.
+    pub fn shortest_path(&self) -> Option<u64> {
+        self.search(false, 0)
+    }
+
+    /// The fewest steps from `AA` to `ZZ` when the inner portals lead a level down and the
+    /// outer ones a level up. Outer portals don't work on the outermost level, and `ZZ` only
+    /// counts there.
+    ///
+    /// The search goes at most `max_depth` levels down, since a maze with no way out would
+    /// otherwise be searched forever. No depth is known to be enough for every maze, so a path
+    /// that needs to go deeper isn't found: a `None` may just mean `max_depth` was too low.
+    pub fn recursive_shortest_path(&self, max_depth: usize) -> Option<u64> {
+        self.search(true, max_depth)
+    }
+
+    fn search(&self, recursive: bool, max_depth: usize) -> Option<u64> {
+        let max_depth = max_depth as isize;
+
+        shortest_distance((self.start, 0), |(portal, depth)| *portal == self.end && *depth == 0, |(portal, depth)| {
+            self.edges[*portal].iter()
+                .filter_map(|edge| {
+                    let depth = if recursive { depth + edge.depth } else { 0 };
+
+                    if depth < 0 || depth > max_depth {
+                        None
+                    } else {
+                        Some(((edge.to, depth), edge.cost))
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Route {
+    pub key: usize,
+    pub cost: u64,
+    /// The doors on the way, as a bitmask of the keys that open them. A key can have a route
+    /// for each of the different sets of doors its shortest paths go through.
+    pub doors: u32,
+}
+
+/// A vault of keys (`a`-`z`) and the doors they open (`A`-`Z`), explored by one or more robots
+/// (`@`), reduced to the routes from each robot and key to every key.
+#[derive(Debug)]
+pub struct Vault {
+    grid: Grid<char>,
+    robots: Vec<Point>,
+    /// The keys by their letter; the bit for key `i` is `1 << i`.
+    keys: Vec<Option<Point>>,
+    /// The routes from each robot, and then from each key there is.
+    routes: Vec<Vec<Route>>,
+    /// Where the routes from each key are.
+    key_nodes: Vec<Option<usize>>,
+}
+
+impl Vault {
+    pub fn parse(input: &str) -> Result<Vault, ParseError> {
+        let grid = Grid::from_lines(input, '#', |c| match c {
+            '#' | '.' | '@' | 'a'..='z' | 'A'..='Z' => Some(c),
+            _ => None,
+        })?;
+
+        if grid.positions(|c| c == '@').next().is_none() {
+            return Err(error_at((0, 0), "there is no robot in the vault".to_owned()));
+        }
+
+        Ok(Vault::from_grid(grid))
+    }
+
+    fn from_grid(grid: Grid<char>) -> Vault {
+        let robots: Vec<Point> = grid.positions(|c| c == '@').collect();
+        let mut keys = vec![None; 26];
+        for (x, y, c) in grid.iter() {
+            if c.is_ascii_lowercase() {
+                keys[(c as u8 - b'a') as usize] = Some((x, y));
+            }
+        }
+
+        // Doors without a key anywhere in the vault can't be opened, so they don't count.
+        let all_keys = key_mask(&keys);
+        let routes = robots.iter().cloned().chain(keys.iter().flatten().cloned())
+            .map(|from| {
+                let doors = door_sets(&grid, from, all_keys);
+
+                keys.iter().enumerate()
+                    .filter_map(|(key, position)| Some((key, doors.get(&(*position)?)?)))
+                    .filter(|(_, (cost, _))| *cost > 0)
+                    .flat_map(|(key, (cost, sets))| sets.iter().map(move |doors| Route { key, cost: *cost, doors: *doors }))
+                    .collect()
+            })
+            .collect();
+
+        let mut key_nodes = vec![None; keys.len()];
+        let mut node = robots.len();
+        for (key, position) in keys.iter().enumerate() {
+            if position.is_some() {
+                key_nodes[key] = Some(node);
+                node += 1;
+            }
+        }
+
+        Vault { grid, robots, keys, routes, key_nodes }
+    }
+
+    /// Walls in the cells around the robot and puts a robot in each corner, like day 18 part 2.
+    /// Needs a single robot with all eight of those cells inside the vault.
+    pub fn with_four_robots(&self) -> Option<Vault> {
+        let (x, y) = match self.robots[..] {
+            [robot] => robot,
+            _ => return None,
+        };
+
+        let (width, height) = self.grid.size();
+        if x < 1 || y < 1 || x + 1 >= width as isize || y + 1 >= height as isize {
+            return None;
+        }
+
+        let mut grid = self.grid.clone();
+        for (dx, dy) in [(-1, -1), (0, -1), (1, -1), (-1, 0), (0, 0), (1, 0), (-1, 1), (0, 1), (1, 1)].iter() {
+            grid.set(x + dx, y + dy, if dx * dy != 0 { '@' } else { '#' });
+        }
+
+        Some(Vault::from_grid(grid))
+    }
+
+    pub fn robots(&self) -> &[Point] {
+        &self.robots
+    }
+
+    pub fn robot_routes(&self, robot: usize) -> &[Route] {
+        &self.routes[robot]
+    }
+
+    pub fn key_routes(&self, key: usize) -> &[Route] {
+        self.key_nodes[key].map(|node| &self.routes[node][..]).unwrap_or(&[])
+    }
+
+    /// The fewest steps it takes the robots to pick up every key. Only one robot moves at a
+    /// time, and a door can only be passed once its key has been picked up.
+    pub fn collect_keys(&self) -> Option<u64> {
+        let all_keys = key_mask(&self.keys);
+
+        // Each robot is at a node: its starting point, or the last key it picked up.
+        let start: (u32, Vec<usize>) = (0, (0..self.robots.len()).collect());
+
+        shortest_distance(start, |(keys, _)| *keys == all_keys, |(keys, nodes)| {
+            let mut next = Vec::new();
+
+            for (robot, node) in nodes.iter().enumerate() {
+                for route in self.routes[*node].iter() {
+                    let bit = 1 << route.key;
+                    if keys & bit != 0 || route.doors & !keys != 0 {
+                        continue;
+                    }
+
+                    let mut nodes = nodes.clone();
+                    nodes[robot] = self.key_nodes[route.key].unwrap();
+                    next.push(((keys | bit, nodes), route.cost));
+                }
+            }
+
+            next
+        })
+    }
+}
+
+/// The distance to every cell from `from`, and the doors on the shortest paths there. When the
+/// shortest paths go through different doors, each set of doors that isn't a superset of another
+/// is kept. Longer paths are never considered, even when they would avoid a door.
+fn door_sets(grid: &Grid<char>, from: Point, all_keys: u32) -> HashMap<Point, (u64, Vec<u32>)> {
+    let distances = bfs(grid, from, |c| c != '#', orthogonal);
+    let mut cells: Vec<(Point, u64)> = distances.iter().collect();
+    cells.sort_unstable_by_key(|(_, distance)| *distance);
+
+    let mut doors: HashMap<Point, (u64, Vec<u32>)> = HashMap::with_capacity(cells.len());
+    for (point, distance) in cells {
+        let c = grid.get(point.0, point.1);
+        let door = if c.is_ascii_uppercase() { (1 << (c as u8 - b'A')) & all_keys } else { 0 };
+
+        let mut sets: Vec<u32> = if distance == 0 {
+            vec![door]
+        } else {
+            IntoIterator::into_iter(orthogonal(point))
+                .filter_map(|p| doors.get(&p).filter(|(d, _)| *d + 1 == distance))
+                .flat_map(|(_, sets)| sets.iter().map(|set| set | door))
+                .collect()
+        };
+        sets.sort_unstable();
+        sets.dedup();
+        let minimal = sets.iter().cloned()
+            .filter(|set| !sets.iter().any(|other| other != set && other & !set == 0))
+            .collect();
+
+        doors.insert(point, (distance, minimal));
+    }
+
+    doors
+}
+
+fn key_mask(keys: &[Option<Point>]) -> u32 {
+    keys.iter().enumerate().filter(|(_, k)| k.is_some()).fold(0, |mask, (i, _)| mask | 1 << i)
+}
+
+/// Makes a grid out of lines that might not be the same length, padding them with spaces.
+pub fn parse_padded(input: &str) -> Grid<char> {
+    let lines: Vec<&str> = input.trim_end_matches(['\n', '\r']).lines().collect();
+    let width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    let mut grid = Grid::new(width, lines.len(), 0, 0, ' ');
+
+    for (y, line) in lines.iter().enumerate() {
+        for (x, c) in line.chars().enumerate() {
+            grid.set(x as isize, y as isize, c);
+        }
+    }
+
+    grid
+}
+
+fn find_portals(grid: &Grid<char>) -> Result<Vec<Portal>, ParseError> {
+    // The outside edge is taken from the maze itself rather than the size of the grid, which
+    // depends on where the labels are and whether trailing spaces were trimmed.
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (isize::MAX, isize::MAX, isize::MIN, isize::MIN);
+    for (x, y, _) in grid.iter().filter(|(_, _, c)| *c == '#' || *c == '.') {
+        min_x = min_x.min(x);
+        min_y = min_y.min(y);
+        max_x = max_x.max(x);
+        max_y = max_y.max(y);
+    }
+
+    let mut portals: Vec<Portal> = Vec::new();
+
+    for (x, y, c) in grid.iter() {
+        if !c.is_ascii_uppercase() {
+            continue;
+        }
+
+        // Labels read left to right or top to bottom, so each is found from its first letter.
+        for (dx, dy) in [(1, 0), (0, 1)].iter().cloned() {
+            let second = grid.get_oob(x + dx, y + dy);
+            if !second.is_ascii_uppercase() {
+                continue;
+            }
+
+            let label: String = [c, second].iter().collect();
+            let position = [(x + dx * 2, y + dy * 2), (x - dx, y - dy)].iter().cloned()
+                .find(|(px, py)| grid.get_oob(*px, *py) == '.')
+                .ok_or_else(|| error_at((x, y), format!("{} is not next to the maze", label)))?;
+            let outer = position.0 == min_x || position.1 == min_y || position.0 == max_x || position.1 == max_y;
+
+            portals.push(Portal { label, position, outer });
+        }
+    }
+
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for portal in portals.iter() {
+        *counts.entry(&portal.label).or_insert(0) += 1;
+    }
+    for portal in portals.iter() {
+        let expected = if portal.label == START || portal.label == END { 1 } else { 2 };
+        if counts[portal.label.as_str()] != expected {
+            return Err(error_at(portal.position, format!("{} should appear {} time(s)", portal.label, expected)));
+        }
+    }
+
+    Ok(portals)
+}
+
+fn error_at((x, y): Point, message: String) -> ParseError {
+    ParseError { line: y as usize + 1, column: x as usize + 1, message }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DONUT: &str = "         A
+         A
+  #######.#########
+  #######.........#
+  #######.#######.#
+  #######.#######.#
+  #######.#######.#
+  #####  B    ###.#
+BC...##  C    ###.#
+  ##.##       ###.#
+  ##...DE  F  ###.#
+  #####    G  ###.#
+  #########.#####.#
+DE..#######...###.#
+  #.#########.###.#
+FG..#########.....#
+  ###########.#####
+             Z
+             Z
+";
+
+    #[test]
+    fn test_portals() {
+        let maze = DonutMaze::parse(DONUT).unwrap();
+        let labels: Vec<&str> = maze.portals().iter().map(|p| p.label.as_str()).collect();
+
+        assert_eq!(labels.len(), 8);
+        assert!(maze.portals().contains(&Portal { label: "BC".to_owned(), position: (9, 6), outer: false }));
+        assert!(maze.portals().contains(&Portal { label: "BC".to_owned(), position: (2, 8), outer: true }));
+        assert!(maze.portals().contains(&Portal { label: "ZZ".to_owned(), position: (13, 16), outer: true }));
+
+        let start = labels.iter().position(|l| *l == "AA").unwrap();
+        let end = labels.iter().position(|l| *l == "ZZ").unwrap();
+        assert!(maze.edges(start).contains(&Edge { to: end, cost: 26, depth: 0 }));
+
+        let err = DonutMaze::parse(&DONUT.replace("FG..", "FH..")).unwrap_err();
+        assert_eq!(err.message, "FG should appear 2 time(s)");
+    }
+
+    #[test]
+    fn test_portals_without_padding() {
+        // No labels on the right or at the bottom, so the grid ends at the walls there.
+        let maze = DonutMaze::parse("    A   D
+    A   E
+  ##.###.####
+ZZ..........#
+  ### D BC..#
+BC.## E   #.#
+  #.##.####.#
+  #.........#
+  ###########
+").unwrap();
+        let outer = |label: &str, position: Point| maze.portals().iter()
+            .find(|p| p.label == label && p.position == position)
+            .map(|p| p.outer);
+
+        assert_eq!(outer("AA", (4, 2)), Some(true));
+        assert_eq!(outer("ZZ", (2, 3)), Some(true));
+        assert_eq!(outer("BC", (2, 5)), Some(true));
+        assert_eq!(outer("BC", (10, 4)), Some(false));
+        assert_eq!(outer("DE", (8, 2)), Some(true));
+        assert_eq!(outer("DE", (6, 6)), Some(false));
+    }
+
+    #[test]
+    fn test_vault() {
+        let vault = Vault::parse("#########\n#b.A.@.a#\n#########\n").unwrap();
+        assert_eq!(vault.robot_routes(0), &[Route { key: 0, cost: 2, doors: 0 }, Route { key: 1, cost: 4, doors: 1 }]);
+        assert_eq!(vault.key_routes(0), &[Route { key: 1, cost: 6, doors: 1 }]);
+        assert_eq!(vault.collect_keys(), Some(8));
+
+        let vault = Vault::parse("\
+########################
+#f.D.E.e.C.b.A.@.a.B.c.#
+######################.#
+#d.....................#
+########################
+").unwrap();
+        assert_eq!(vault.collect_keys(), Some(86));
+
+        let vault = Vault::parse("\
+#################
+#i.G..c...e..H.p#
+########.########
+#j.A..b...f..D.o#
+########@########
+#k.E..a...g..B.n#
+########.########
+#l.F..d...h..C.m#
+#################
+").unwrap();
+        assert_eq!(vault.collect_keys(), Some(136));
+
+        assert!(Vault::parse("###\n#a#\n###\n").is_err());
+    }
+
+    #[test]
+    fn test_vault_equal_paths() {
+        // Both ways to `a` are 4 steps, one through A and the other through B.
+        let vault = Vault::parse("#######\n##.A.##\n#b@#a##\n##.B.##\n#######\n").unwrap();
+        let to_a: Vec<u32> = vault.robot_routes(0).iter().filter(|r| r.key == 0).map(|r| r.doors).collect();
+
+        assert_eq!(to_a, vec![0b01, 0b10]);
+        assert_eq!(vault.collect_keys(), Some(6));
+    }
+
+    #[test]
+    fn test_four_robots() {
+        let vault = Vault::parse("#######\n#a.#Cd#\n##...##\n##.@.##\n##...##\n#cB#Ab#\n#######\n").unwrap();
+        let split = vault.with_four_robots().unwrap();
+        assert_eq!(split.robots().len(), 4);
+        assert_eq!(split.collect_keys(), Some(8));
+
+        let vault = Vault::parse("\
+###############
+#d.ABC.#.....a#
+######@#@######
+###############
+######@#@######
+#b.....#.....c#
+###############
+").unwrap();
+        assert!(vault.with_four_robots().is_none());
+        assert_eq!(vault.collect_keys(), Some(24));
+    }
+
+    #[test]
+    fn test_four_robots_at_the_edge() {
+        assert!(Vault::parse("@a\n..\n").unwrap().with_four_robots().is_none());
+        assert!(Vault::parse("...\n..@\n...\n").unwrap().with_four_robots().is_none());
+        assert!(Vault::parse("...\n.@.\n.a.\n").unwrap().with_four_robots().is_some());
+    }
+
+    #[test]
+    fn test_shortest_path() {
+        let maze = DonutMaze::parse(DONUT).unwrap();
+
+        assert_eq!(maze.shortest_path(), Some(23));
+        assert_eq!(maze.recursive_shortest_path(0), Some(26));
+        assert_eq!(maze.recursive_shortest_path(10), Some(26));
+    }
+
+    #[test]
+    fn test_recursive_shortest_path_depth() {
+        // The only way to ZZ is down through BC and back up through DE.
+        let maze = DonutMaze::parse("        A
+        A
+  ######.########
+  ######.########
+  ######.########
+  ###   B     ###
+  ###   C     ###
+BC.##       DE...ZZ
+  .##         ###
+  .##         ###
+  .##############
+DE.##############
+  ###############
+").unwrap();
+
+        assert_eq!(maze.shortest_path(), Some(10));
+        assert_eq!(maze.recursive_shortest_path(0), None);
+        assert_eq!(maze.recursive_shortest_path(1), Some(10));
+    }
+}
@@ -0,0 +1,444 @@
+use std::convert::TryFrom;
+use std::fmt::{self, Display};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    /// Moves an error from parsing a single line to where that line is in the whole input.
+    pub fn on_line(mut self, line: usize) -> ParseError {
+        self.line += line - 1;
+        self
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
+    }
+}
+
+/// Reads the input byte by byte. Positions are only worked out when there is an error, so
+/// keeping track of them costs nothing on the happy path.
+pub struct Scanner<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    pub fn new(input: &'a str) -> Scanner<'a> {
+        Scanner::from_bytes(input.as_bytes())
+    }
+
+    pub fn from_bytes(data: &'a [u8]) -> Scanner<'a> {
+        Scanner { data, pos: 0 }
+    }
+
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    #[inline]
+    pub fn peek(&self) -> Option<u8> {
+        self.data.get(self.pos).cloned()
+    }
+
+    #[inline]
+    pub fn take(&mut self) -> Option<u8> {
+        let byte = self.peek()?;
+        self.pos += 1;
+
+        Some(byte)
+    }
+
+    #[inline]
+    pub fn error(&self, message: impl Into<String>) -> ParseError {
+        self.error_at(self.pos, message)
+    }
+
+    #[inline]
+    fn error_at(&self, pos: usize, message: impl Into<String>) -> ParseError {
+        error_in(self.data, pos, message.into())
+    }
+
+    /// An error at the end of the input, for when something is missing from it.
+    pub fn error_at_end(&self, message: impl Into<String>) -> ParseError {
+        self.error_at(self.data.len(), message)
+    }
+
+    /// An error saying what was expected and what was found instead.
+    #[inline]
+    pub fn unexpected(&self, expected: &str) -> ParseError {
+        unexpected_in(self.data, self.pos, expected)
+    }
+
+    #[inline]
+    pub fn accept(&mut self, byte: u8) -> bool {
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    #[inline]
+    pub fn expect(&mut self, byte: u8) -> Result<(), ParseError> {
+        if self.accept(byte) {
+            Ok(())
+        } else {
+            Err(unexpected_byte_in(self.data, self.pos, byte))
+        }
+    }
+
+    pub fn expect_str(&mut self, s: &str) -> Result<(), ParseError> {
+        if self.data[self.pos..].starts_with(s.as_bytes()) {
+            self.pos += s.len();
+            Ok(())
+        } else {
+            Err(self.unexpected(&format!("{:?}", s)))
+        }
+    }
+
+    /// Takes bytes while `pred` holds. The predicate should only accept ASCII, or the result
+    /// might not be valid UTF-8.
+    pub fn take_while(&mut self, pred: impl Fn(u8) -> bool) -> &'a str {
+        let start = self.pos;
+        while self.peek().map(&pred).unwrap_or(false) {
+            self.pos += 1;
+        }
+
+        std::str::from_utf8(&self.data[start..self.pos]).unwrap_or_default()
+    }
+
+    /// Takes a name made of ASCII letters, digits and underscores.
+    pub fn ident(&mut self) -> Result<&'a str, ParseError> {
+        let ident = self.take_while(|b| b.is_ascii_alphanumeric() || b == b'_');
+        if ident.is_empty() {
+            return Err(self.unexpected("a name"));
+        }
+
+        Ok(ident)
+    }
+
+    /// Expects `separator`, with any spaces around it.
+    pub fn separator(&mut self, separator: &str) -> Result<(), ParseError> {
+        self.skip_spaces();
+        self.expect_str(separator)?;
+        self.skip_spaces();
+
+        Ok(())
+    }
+
+    /// Skips spaces and tabs.
+    #[inline]
+    pub fn skip_spaces(&mut self) {
+        while let Some(b' ') | Some(b'\t') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    /// Skips spaces, tabs and line breaks.
+    #[inline]
+    pub fn skip_whitespace(&mut self) {
+        while let Some(b' ') | Some(b'\t') | Some(b'\r') | Some(b'\n') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    /// Expects the end of the line (or of the input).
+    #[inline]
+    pub fn newline(&mut self) -> Result<(), ParseError> {
+        self.accept(b'\r');
+
+        if self.accept(b'\n') || self.is_done() {
+            Ok(())
+        } else {
+            Err(self.unexpected("end of line"))
+        }
+    }
+
+    /// Expects nothing but whitespace until the end of the input.
+    #[inline]
+    pub fn end(&mut self) -> Result<(), ParseError> {
+        self.skip_whitespace();
+
+        if self.is_done() {
+            Ok(())
+        } else {
+            Err(self.unexpected("end of input"))
+        }
+    }
+
+    pub fn digit(&mut self) -> Result<u8, ParseError> {
+        match self.peek() {
+            Some(b @ b'0'..=b'9') => {
+                self.pos += 1;
+                Ok(b - b'0')
+            }
+            _ => Err(self.unexpected("a digit")),
+        }
+    }
+
+    #[inline]
+    pub fn unsigned<T: TryFrom<u64>>(&mut self) -> Result<T, ParseError> {
+        let start = self.pos;
+        let value = self.digits()?;
+
+        T::try_from(value).map_err(|_| out_of_range_in(self.data, start))
+    }
+
+    // Always inlined: left to itself, the compiler keeps it out of line in parsers that call
+    // it several times, like day 12's, and they end up slower than what they replaced.
+    #[inline(always)]
+    pub fn signed<T: TryFrom<i64>>(&mut self) -> Result<T, ParseError> {
+        let start = self.pos;
+        let negative = self.accept(b'-');
+        let value = self.digits()?;
+
+        // -i64::MIN doesn't fit in an i64, so the magnitude is negated as a u64 instead.
+        let value = if negative { value.wrapping_neg() as i64 } else { value as i64 };
+        if (value < 0) != (negative && value != 0) {
+            return Err(out_of_range_in(self.data, start));
+        }
+
+        T::try_from(value).map_err(|_| out_of_range_in(self.data, start))
+    }
+
+    #[inline]
+    fn digits(&mut self) -> Result<u64, ParseError> {
+        let rest = &self.data[self.pos..];
+        let mut value: u64 = 0;
+        let mut len = 0;
+
+        // Any 19 digits fit in a u64, so only longer numbers need checking, away from here.
+        let max = rest.len().min(19);
+        while len < max {
+            let digit = rest[len].wrapping_sub(b'0');
+            if digit > 9 {
+                break;
+            }
+
+            value = value * 10 + digit as u64;
+            len += 1;
+        }
+
+        if len == 0 || len == 19 {
+            let (value, end) = checked_digits_in(self.data, self.pos)?;
+            self.pos = end;
+
+            return Ok(value);
+        }
+        self.pos += len;
+
+        Ok(value)
+    }
+
+    /// Reads one or more items with `separator` between them.
+    pub fn separated<T>(&mut self, separator: u8, mut item: impl FnMut(&mut Self) -> Result<T, ParseError>) -> Result<Vec<T>, ParseError> {
+        let mut items = vec![item(self)?];
+        while self.accept(separator) {
+            items.push(item(self)?);
+        }
+
+        Ok(items)
+    }
+
+    /// Like `separated`, but hands out the items one at a time instead of collecting them.
+    /// Iteration stops after the first error.
+    pub fn list<T, F>(&mut self, separator: u8, item: F) -> List<'_, 'a, F>
+        where F: FnMut(&mut Self) -> Result<T, ParseError>
+    {
+        List { scanner: self, separator, item, first: true, done: false }
+    }
+}
+
+// The scanner's error paths. They take the input and the position rather than the scanner
+// itself, so that the scanner never has its address taken and can live in registers.
+
+#[cold]
+fn error_in(data: &[u8], pos: usize, message: String) -> ParseError {
+    let before = &data[..pos.min(data.len())];
+    let line_start = before.iter().rposition(|b| *b == b'\n').map(|i| i + 1).unwrap_or(0);
+    let column = String::from_utf8_lossy(&before[line_start..]).chars().count() + 1;
+
+    ParseError {
+        line: before.iter().filter(|b| **b == b'\n').count() + 1,
+        column,
+        message,
+    }
+}
+
+#[cold]
+fn unexpected_in(data: &[u8], pos: usize, expected: &str) -> ParseError {
+    let found = match data.get(pos..) {
+        Some(rest) if !rest.is_empty() => {
+            let rest = String::from_utf8_lossy(&rest[..rest.len().min(4)]).into_owned();
+            let c = rest.chars().next().unwrap();
+
+            match c {
+                '\n' | '\r' => "end of line".to_owned(),
+                c => format!("{:?}", c),
+            }
+        }
+        _ => "end of input".to_owned(),
+    };
+
+    error_in(data, pos, format!("expected {}, found {}", expected, found))
+}
+
+#[cold]
+fn out_of_range_in(data: &[u8], pos: usize) -> ParseError {
+    error_in(data, pos, "number out of range".to_owned())
+}
+
+#[cold]
+fn unexpected_byte_in(data: &[u8], pos: usize, byte: u8) -> ParseError {
+    unexpected_in(data, pos, &format!("{:?}", byte as char))
+}
+
+/// Reads a number that might not fit in a u64, or that might not be there at all, and returns
+/// it along with where it ends.
+#[cold]
+fn checked_digits_in(data: &[u8], start: usize) -> Result<(u64, usize), ParseError> {
+    let mut value: u64 = 0;
+    let mut pos = start;
+
+    while let Some(digit @ b'0'..=b'9') = data.get(pos).cloned() {
+        value = value.checked_mul(10)
+            .and_then(|v| v.checked_add((digit - b'0') as u64))
+            .ok_or_else(|| out_of_range_in(data, start))?;
+        pos += 1;
+    }
+
+    if pos == start {
+        return Err(unexpected_in(data, pos, "a number"));
+    }
+
+    Ok((value, pos))
+}
+
+pub struct List<'s, 'a, F> {
+    scanner: &'s mut Scanner<'a>,
+    separator: u8,
+    item: F,
+    first: bool,
+    done: bool,
+}
+
+impl<'s, 'a, T, F> Iterator for List<'s, 'a, F>
+    where F: FnMut(&mut Scanner<'a>) -> Result<T, ParseError>
+{
+    type Item = Result<T, ParseError>;
+
+    fn next(&mut self) -> Option<Result<T, ParseError>> {
+        if self.done || (!self.first && !self.scanner.accept(self.separator)) {
+            self.done = true;
+            return None;
+        }
+        self.first = false;
+
+        let item = (self.item)(self.scanner);
+        self.done = item.is_err();
+
+        Some(item)
+    }
+}
+
+/// Benchmarks a scanner-based parser against the hand-rolled loop it replaced, and checks that
+/// both give the same result and the scanner is no slower. The two take turns a batch at a
+/// time and the batches are compared in pairs, so that the machine speeding up or slowing down
+/// part of the way through doesn't favour either. Timings only mean something in release builds
+/// without other tests running alongside, so the callers are ignored by default and run with
+/// `cargo test --release -- --ignored --test-threads=1 bench_scanner`.
+#[cfg(test)]
+pub(crate) fn assert_scanner_speed<T: PartialEq + fmt::Debug>(mut hand_rolled: impl FnMut() -> T, mut scanner: impl FnMut() -> T) {
+    use crate::aoc::format_time;
+    use crate::bench::{bench, BenchOptions};
+
+    const WARMUP_ROUNDS: usize = 20;
+    const ROUNDS: usize = 200;
+
+    let options = BenchOptions::iterations(20);
+    let mut rounds = Vec::with_capacity(ROUNDS);
+    for round in 0..WARMUP_ROUNDS + ROUNDS {
+        let (expected, hand) = bench(&options, &mut hand_rolled);
+        let (actual, scanned) = bench(&options, &mut scanner);
+        assert_eq!(actual, expected);
+
+        if round >= WARMUP_ROUNDS {
+            rounds.push((scanned.median as f64 / hand.median as f64, hand.median, scanned.median));
+        }
+    }
+
+    rounds.sort_unstable_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    let (ratio, hand, scanned) = rounds[ROUNDS / 2];
+
+    assert!(ratio <= 1.0, "the scanner took {:.0}% longer than the hand-rolled loop ({} against {})",
+            (ratio - 1.0) * 100.0, format_time(scanned), format_time(hand));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numbers() {
+        let mut scanner = Scanner::new("12,-7,0\n");
+
+        assert_eq!(scanner.separated(b',', |s| s.signed::<i32>()), Ok(vec![12, -7, 0]));
+        assert_eq!(scanner.end(), Ok(()));
+
+        assert_eq!(Scanner::new("-9223372036854775808").signed::<i64>(), Ok(i64::MIN));
+        assert_eq!(Scanner::new("18446744073709551615").unsigned::<u64>(), Ok(u64::MAX));
+        assert!(Scanner::new("18446744073709551616").unsigned::<u64>().is_err());
+        assert!(Scanner::new("256").unsigned::<u8>().is_err());
+        assert!(Scanner::new("-1").unsigned::<u32>().is_err());
+        assert!(Scanner::new("-9223372036854775809").signed::<i64>().is_err());
+        assert!(Scanner::new("9223372036854775808").signed::<i64>().is_err());
+        assert_eq!(Scanner::new("-0").signed::<i64>(), Ok(0));
+        assert_eq!(Scanner::new("0000000000000000000012").unsigned::<u8>(), Ok(12));
+    }
+
+    #[test]
+    fn test_idents_and_lists() {
+        let mut scanner = Scanner::from_bytes(b"7 A_1,2 B => 3 C\n");
+        let items = scanner.separated(b',', |s| {
+            let amount = s.unsigned::<u32>()?;
+            s.skip_spaces();
+            Ok((amount, s.ident()?))
+        });
+        assert_eq!(items, Ok(vec![(7, "A_1"), (2, "B")]));
+        assert_eq!(scanner.separator("=>"), Ok(()));
+        assert_eq!(scanner.unsigned::<u32>(), Ok(3));
+        assert_eq!(scanner.separator("=>").unwrap_err().message, "expected \"=>\", found 'C'");
+
+        let mut scanner = Scanner::new("1,2,x,4");
+        let items: Vec<_> = scanner.list(b',', |s| s.unsigned::<u8>()).collect();
+        assert_eq!(items.len(), 3);
+        assert!(items[2].is_err());
+
+        assert_eq!(Scanner::new("-").ident().unwrap_err().message, "expected a name, found '-'");
+    }
+
+    #[test]
+    fn test_error_position() {
+        let mut scanner = Scanner::new("1,2\n3,x4\n");
+        scanner.separated(b',', |s| s.unsigned::<u32>()).unwrap();
+        scanner.newline().unwrap();
+
+        let err = scanner.separated(b',', |s| s.unsigned::<u32>()).unwrap_err();
+        assert_eq!(err.to_string(), "line 2, column 3: expected a number, found 'x'");
+
+        let mut scanner = Scanner::new("5 6");
+        scanner.unsigned::<u32>().unwrap();
+        assert_eq!(scanner.end().unwrap_err().column, 3);
+
+        assert_eq!(Scanner::new("").unsigned::<u32>().unwrap_err().message, "expected a number, found end of input");
+    }
+}
@@ -0,0 +1,74 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+pub fn available_threads() -> usize {
+    thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+}
+
+/// Runs `job` on every item on up to `threads` threads. The results are handed to `done` on the
+/// calling thread in the order of the items, each as soon as it and everything before it is
+/// finished.
+pub fn run_ordered<T: Sync, R: Send>(items: &[T], threads: usize, job: impl Fn(&T) -> R + Sync, mut done: impl FnMut(&T, R)) {
+    if threads <= 1 || items.len() <= 1 {
+        for item in items.iter() {
+            done(item, job(item));
+        }
+
+        return;
+    }
+
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..threads.min(items.len()) {
+            let sender = sender.clone();
+            let (next, job) = (&next, &job);
+
+            scope.spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                if index >= items.len() {
+                    break;
+                }
+
+                if sender.send((index, job(&items[index]))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        let mut pending: Vec<Option<R>> = (0..items.len()).map(|_| None).collect();
+        let mut current = 0;
+        for (index, result) in receiver {
+            pending[index] = Some(result);
+
+            while let Some(result) = pending.get_mut(current).and_then(|r| r.take()) {
+                done(&items[current], result);
+                current += 1;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_run_ordered() {
+        let items: Vec<u64> = (0..20).collect();
+
+        for threads in [1, 4] {
+            let mut results = Vec::new();
+            run_ordered(&items, threads, |n| {
+                thread::sleep(Duration::from_millis(20 - n));
+                n * 2
+            }, |n, r| results.push((*n, r)));
+
+            assert_eq!(results, items.iter().map(|n| (*n, n * 2)).collect::<Vec<_>>());
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::aoc::{answer_path, day_name, input_path, project_dir};
+
+pub fn day_template(day: u32) -> String {
+    let name = format!("Day{:02}", day);
+
+    format!(r#"use crate::aoc::Solution;
+use crate::parse::ParseError;
+
+pub struct {name};
+
+impl Solution for {name} {{
+    type Input = Vec<String>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    const DAY: u32 = {day};
+
+    fn parse(input: &str) -> Result<Vec<String>, ParseError> {{
+        Ok(parse_input(input))
+    }}
+
+    fn part1(lines: &Vec<String>) -> usize {{
+        part1(lines)
+    }}
+
+    fn part2(lines: &Vec<String>) -> usize {{
+        part2(lines)
+    }}
+}}
+
+fn parse_input(input: &str) -> Vec<String> {{
+    input.lines().map(|l| l.to_owned()).collect()
+}}
+
+fn part1(lines: &[String]) -> usize {{
+    lines.len()
+}}
+
+fn part2(_lines: &[String]) -> usize {{
+    0
+}}
+
+#[cfg(test)]
+mod tests {{
+    use super::*;
+
+    #[test]
+    fn test_part1() {{
+        assert_eq!(part1(&parse_input("a\nb\n")), 2);
+    }}
+}}
+"#, name = name, day = day)
+}
+
+/// Adds the `pub mod` line and the registry entry for `day` to the source of `days/mod.rs`,
+/// keeping both lists sorted.
+pub fn register_day(source: &str, day: u32) -> Result<String, String> {
+    let name = day_name(day);
+    let module = format!("pub mod {};", name);
+    let entry = format!("        Day::new::<{}::Day{:02}>(),", name, day);
+
+    if source.lines().any(|l| l == module) {
+        return Err(format!("{} is already registered.", name));
+    }
+
+    let mut lines: Vec<&str> = source.lines().collect();
+    insert_sorted(&mut lines, "pub mod day", &module)?;
+    insert_sorted(&mut lines, "        Day::new::<day", &entry)?;
+
+    Ok(lines.join("\n") + "\n")
+}
+
+fn insert_sorted<'a>(lines: &mut Vec<&'a str>, prefix: &str, line: &'a str) -> Result<(), String> {
+    let matching: Vec<usize> = (0..lines.len()).filter(|i| lines[*i].starts_with(prefix)).collect();
+    let index = match matching.iter().find(|i| lines[**i] > line) {
+        Some(i) => *i,
+        None => match matching.last() {
+            Some(i) => i + 1,
+            None => return Err(format!("Could not find where to put `{}`.", line.trim())),
+        },
+    };
+
+    lines.insert(index, line);
+
+    Ok(())
+}
+
+/// Creates the source file for `day`, registers it and adds empty input and answer files.
+/// Returns the files that were written.
+///
+/// The registry is written last, so a failure part way leaves it untouched and the new files
+/// are removed again.
+pub fn new_day(day: u32) -> Result<Vec<PathBuf>, String> {
+    let days_dir = project_dir().join("src").join("days");
+    let source_path = days_dir.join(format!("{}.rs", day_name(day)));
+    let mod_path = days_dir.join("mod.rs");
+
+    if source_path.exists() {
+        return Err(format!("{} already exists.", source_path.display()));
+    }
+
+    let registry = fs::read_to_string(&mod_path)
+        .map_err(|e| format!("Could not read {}: {}", mod_path.display(), e))?;
+    let registry = register_day(&registry, day)?;
+
+    let mut files = vec![(source_path, day_template(day))];
+    for path in Some(input_path(&day_name(day))).into_iter()
+        .chain((1..=2).map(|part| answer_path(&day_name(day), part)))
+    {
+        if !path.exists() {
+            files.push((path, String::new()));
+        }
+    }
+
+    let mut written = Vec::with_capacity(files.len() + 1);
+    for (path, data) in files {
+        if let Err(e) = write_file(&path, &data) {
+            remove_files(&written);
+            return Err(e);
+        }
+
+        written.push(path);
+    }
+
+    if let Err(e) = fs::write(&mod_path, registry) {
+        remove_files(&written);
+        return Err(format!("Could not write {}: {}", mod_path.display(), e));
+    }
+    written.push(mod_path);
+
+    Ok(written)
+}
+
+fn write_file(path: &Path, data: &str) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Could not create {}: {}", dir.display(), e))?;
+    }
+
+    fs::write(path, data).map_err(|e| format!("Could not write {}: {}", path.display(), e))
+}
+
+fn remove_files(paths: &[PathBuf]) {
+    for path in paths {
+        let _ = fs::remove_file(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REGISTRY: &str = "pub mod day01;
+pub mod day03;
+
+use crate::aoc::Day;
+
+pub fn registry() -> Vec<Day> {
+    vec![
+        Day::new::<day01::Day01>(),
+        Day::new::<day03::Day03>(),
+    ]
+}
+";
+
+    #[test]
+    fn test_register_day() {
+        let registry = register_day(REGISTRY, 2).unwrap();
+        assert_eq!(registry, REGISTRY
+            .replace("pub mod day03;", "pub mod day02;\npub mod day03;")
+            .replace("        Day::new::<day03", "        Day::new::<day02::Day02>(),\n        Day::new::<day03"));
+
+        let registry = register_day(REGISTRY, 25).unwrap();
+        assert!(registry.contains("pub mod day03;\npub mod day25;\n"));
+        assert!(registry.contains("Day03>(),\n        Day::new::<day25::Day25>(),\n    ]"));
+
+        assert!(register_day(REGISTRY, 3).is_err());
+    }
+
+    #[test]
+    fn test_day_template() {
+        let source = day_template(7);
+
+        assert!(source.contains("pub struct Day07;"));
+        assert!(source.contains("const DAY: u32 = 7;"));
+    }
+}
@@ -0,0 +1,35 @@
+use common::aoc::{RunOptions, load_answer, load_input, verify_report};
+use common::days::registry;
+
+#[test]
+fn test_answers() {
+    let mut checked = 0;
+    let mut failures: Vec<String> = Vec::new();
+
+    for day in registry() {
+        let name = day.name();
+        let input = match load_input(&name) {
+            Ok(input) => input,
+            Err(ref e) if e.is_not_found() => continue,
+            Err(e) => panic!("{}", e),
+        };
+        if load_answer(&name, 1).is_none() && load_answer(&name, 2).is_none() {
+            continue;
+        }
+
+        let report = (day.run)(&input, &RunOptions::default());
+        for mismatch in verify_report(&report) {
+            if mismatch.is_unrecorded() {
+                println!("{}", mismatch);
+            } else {
+                failures.push(mismatch.to_string());
+            }
+        }
+
+        checked += 1;
+    }
+
+    println!("Checked answers for {} day(s).", checked);
+
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}